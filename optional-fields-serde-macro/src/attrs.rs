@@ -0,0 +1,242 @@
+use proc_macro2::Ident;
+use syn::{Attribute, Error, Expr, Lit, LitStr, Meta, NestedMeta, Path};
+
+/// Options given to a member with `#[field(...)]` attributes
+#[derive(Default)]
+pub(crate) struct FieldAttrs {
+    pub required: bool,
+    pub required_on: Vec<Ident>,
+    pub non_null: bool,
+    pub non_empty: bool,
+    pub len_min: Option<Expr>,
+    pub len_max: Option<Expr>,
+    pub nested: bool,
+    pub custom: Vec<Path>,
+    pub column: Option<LitStr>,
+}
+
+impl FieldAttrs {
+    /// Parse all `#[field(...)]` attributes of a member, rejecting keys no derive of this crate
+    /// reads
+    fn from_attrs(attrs: &[Attribute]) -> Result<Self, Error> {
+        let mut res = FieldAttrs::default();
+        for meta in nested_metas(attrs, "field")? {
+            match &meta {
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("required") => {
+                    res.required = true
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("non_null") => {
+                    res.non_null = true
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("non_empty") => {
+                    res.non_empty = true
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("nested") => res.nested = true,
+                NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("len") => {
+                    for bound in &list.nested {
+                        match bound {
+                            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("min") => {
+                                res.len_min = Some(lit_to_expr(&nv.lit)?)
+                            }
+                            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("max") => {
+                                res.len_max = Some(lit_to_expr(&nv.lit)?)
+                            }
+                            _ => {
+                                return Err(Error::new_spanned(
+                                    bound,
+                                    "expected `min = ...` or `max = ...`",
+                                ))
+                            }
+                        }
+                    }
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("required_on") => {
+                    let mode = lit_str(&nv.lit)?;
+                    let variant = match mode.value().as_str() {
+                        "create" => "Create",
+                        "update" => "Update",
+                        _ => {
+                            return Err(Error::new_spanned(
+                                mode,
+                                "expected `\"create\"` or `\"update\"`",
+                            ))
+                        }
+                    };
+                    res.required_on.push(Ident::new(variant, mode.span()));
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("custom") => {
+                    res.custom.push(lit_str(&nv.lit)?.parse()?)
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("column") => {
+                    res.column = Some(lit_str(&nv.lit)?.clone())
+                }
+                _ => return Err(Error::new_spanned(meta, "unknown `field` attribute")),
+            }
+        }
+        Ok(res)
+    }
+
+    /// Does the member have any validation rule?
+    pub(crate) fn has_rules(&self) -> bool {
+        self.required
+            || !self.required_on.is_empty()
+            || self.non_null
+            || self.non_empty
+            || self.len_min.is_some()
+            || self.len_max.is_some()
+            || !self.custom.is_empty()
+    }
+}
+
+/// Options given to a struct with `#[field(...)]` attributes
+pub(crate) struct ContainerAttrs {
+    pub models: Vec<Path>,
+    pub table: Option<Path>,
+}
+
+impl ContainerAttrs {
+    /// Parse all `#[field(...)]` attributes of a struct
+    ///
+    /// Every derive of this crate accepts the keys of all of them, as a derive cannot see the other
+    /// derives of a struct. Keys that no derive reads are rejected.
+    pub(crate) fn from_attrs(attrs: &[Attribute]) -> Result<Self, Error> {
+        let mut res = ContainerAttrs {
+            models: Vec::new(),
+            table: None,
+        };
+        for meta in nested_metas(attrs, "field")? {
+            match &meta {
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("model") => {
+                    res.models.push(lit_str(&nv.lit)?.parse()?)
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("table") => {
+                    res.table = Some(lit_str(&nv.lit)?.parse()?)
+                }
+                _ => return Err(Error::new_spanned(meta, "unknown `field` attribute")),
+            }
+        }
+        Ok(res)
+    }
+
+    /// Parse all `#[field(...)]` attributes of a member of the struct
+    pub(crate) fn field_attrs(&self, attrs: &[Attribute]) -> Result<FieldAttrs, Error> {
+        FieldAttrs::from_attrs(attrs)
+    }
+}
+
+/// Collect the items of all attributes looking like `#[namespace(...)]`
+pub(crate) fn nested_metas(attrs: &[Attribute], namespace: &str) -> Result<Vec<NestedMeta>, Error> {
+    let mut res = Vec::new();
+    for attr in attrs {
+        if attr.path.is_ident(namespace) {
+            match attr.parse_meta()? {
+                Meta::List(list) => res.extend(list.nested),
+                meta => {
+                    return Err(Error::new_spanned(
+                        meta,
+                        format!("expected `#[{}(...)]`", namespace),
+                    ))
+                }
+            }
+        }
+    }
+    Ok(res)
+}
+
+/// Return the `value` of the first `name = "value"` item in the `#[serde(...)]` attributes
+///
+/// Unparsable serde attributes are ignored, serde itself reports errors for them.
+/// For `rename(serialize = "..", deserialize = "..")` the deserialize name is used, as that is
+/// the name incoming patches use.
+pub(crate) fn serde_name_value(attrs: &[Attribute], name: &str) -> Option<String> {
+    for attr in attrs {
+        if !attr.path.is_ident("serde") {
+            continue;
+        }
+        if let Ok(Meta::List(list)) = attr.parse_meta() {
+            for meta in list.nested {
+                match meta {
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident(name) => {
+                        if let Lit::Str(s) = nv.lit {
+                            return Some(s.value());
+                        }
+                    }
+                    NestedMeta::Meta(Meta::List(list)) if list.path.is_ident(name) => {
+                        for meta in list.nested {
+                            if let NestedMeta::Meta(Meta::NameValue(nv)) = meta {
+                                if let (true, Lit::Str(s)) =
+                                    (nv.path.is_ident("deserialize"), nv.lit)
+                                {
+                                    return Some(s.value());
+                                }
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    None
+}
+
+/// The name serde uses on the wire for a member called `ident`
+///
+/// Honours `#[serde(rename = "...")]` on the member and `#[serde(rename_all = "...")]` on
+/// the container.
+pub(crate) fn wire_name(ident: &str, attrs: &[Attribute], rename_all: Option<&str>) -> String {
+    let ident = ident.trim_start_matches("r#");
+    if let Some(name) = serde_name_value(attrs, "rename") {
+        return name;
+    }
+    match rename_all {
+        Some(rule) => apply_rename_rule(ident, rule),
+        None => ident.to_string(),
+    }
+}
+
+/// Apply a serde `rename_all` rule to a snake_case member name
+fn apply_rename_rule(ident: &str, rule: &str) -> String {
+    let pascal = || {
+        ident
+            .split('_')
+            .map(|word| {
+                let mut chars = word.chars();
+                match chars.next() {
+                    Some(first) => first.to_uppercase().chain(chars).collect(),
+                    None => String::new(),
+                }
+            })
+            .collect::<String>()
+    };
+    match rule {
+        "lowercase" => ident.to_lowercase(),
+        "UPPERCASE" | "SCREAMING_SNAKE_CASE" => ident.to_uppercase(),
+        "PascalCase" => pascal(),
+        "camelCase" => {
+            let pascal = pascal();
+            let mut chars = pascal.chars();
+            match chars.next() {
+                Some(first) => first.to_lowercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        }
+        "kebab-case" => ident.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => ident.replace('_', "-").to_uppercase(),
+        _ => ident.to_string(),
+    }
+}
+
+fn lit_str(lit: &Lit) -> Result<&LitStr, Error> {
+    match lit {
+        Lit::Str(s) => Ok(s),
+        _ => Err(Error::new_spanned(lit, "expected a string literal")),
+    }
+}
+
+fn lit_to_expr(lit: &Lit) -> Result<Expr, Error> {
+    match lit {
+        Lit::Str(s) => s.parse(),
+        lit => Ok(syn::parse_quote!(#lit)),
+    }
+}
@@ -0,0 +1,88 @@
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{parse_quote, spanned::Spanned, DeriveInput, Error, Path, Type};
+
+use crate::attrs::ContainerAttrs;
+use crate::util::named_fields;
+use crate::{field_inner_type, is_field};
+
+/// Generate diesel's `AsChangeset` for references to a struct
+///
+/// `Field` members are left out when missing and `Option` members when `None`, like diesel's
+/// own derive does. All other members are always assigned.
+pub(crate) fn derive_field_changeset(input: &DeriveInput) -> Result<TokenStream, Error> {
+    let fields = named_fields(input, "FieldChangeset")?;
+    let container = ContainerAttrs::from_attrs(&input.attrs)?;
+    let table = container.table.clone().ok_or_else(|| {
+        Error::new(
+            Span::call_site(),
+            "`FieldChangeset` requires a `#[field(table = \"...\")]` attribute",
+        )
+    })?;
+
+    let mut tys = Vec::new();
+    let mut exprs = Vec::new();
+    for field in fields {
+        let attrs = container.field_attrs(&field.attrs)?;
+        let ident = field.ident.as_ref().expect("named fields have idents");
+        let column = match &attrs.column {
+            Some(column) => column
+                .parse::<Ident>()
+                .unwrap_or_else(|_| Ident::new_raw(&column.value(), column.span())),
+            None => ident.clone(),
+        };
+        let ty = &field.ty;
+
+        let (changeset_ty, expr) = match ty {
+            Type::Path(path) if is_field(&path.path) => (
+                quote!(Option<diesel::dsl::Eq<#table::#column, &'update #ty>>),
+                quote! {
+                    if self.#ident.is_missing() {
+                        None
+                    } else {
+                        Some(diesel::ExpressionMethods::eq(#table::#column, &self.#ident))
+                    }
+                },
+            ),
+            Type::Path(path) if is_option(&path.path) => {
+                let inner = field_inner_type(&path.path).expect("checked by is_option");
+                (
+                    quote!(Option<diesel::dsl::Eq<#table::#column, &'update #inner>>),
+                    quote! {
+                        self.#ident.as_ref().map(|val| diesel::ExpressionMethods::eq(#table::#column, val))
+                    },
+                )
+            }
+            _ => (
+                quote!(diesel::dsl::Eq<#table::#column, &'update #ty>),
+                quote!(diesel::ExpressionMethods::eq(#table::#column, &self.#ident)),
+            ),
+        };
+        tys.push(quote_spanned!(field.span()=> #changeset_ty));
+        exprs.push(quote_spanned!(field.span()=> #expr));
+    }
+
+    let ident = &input.ident;
+    let mut generics = input.generics.clone();
+    generics.params.push(parse_quote!('update));
+    let (impl_generics, _, _) = generics.split_for_impl();
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics diesel::query_builder::AsChangeset for &'update #ident #ty_generics #where_clause {
+            type Target = #table::table;
+            type Changeset = <(#(#tys,)*) as diesel::query_builder::AsChangeset>::Changeset;
+
+            fn as_changeset(self) -> Self::Changeset {
+                diesel::query_builder::AsChangeset::as_changeset((#(#exprs,)*))
+            }
+        }
+    })
+}
+
+/// Return `true`, if the type path looks like `Option<T>`
+fn is_option(path: &Path) -> bool {
+    path.segments
+        .last()
+        .is_some_and(|segment| segment.ident == "Option")
+        && field_inner_type(path).is_some()
+}
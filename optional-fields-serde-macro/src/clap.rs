@@ -0,0 +1,149 @@
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::{parse_quote, Attribute, Error, Field, Fields, Item, LitStr, Type};
+
+use crate::is_field;
+
+/// Clap's attributes of struct members
+const CLAP_ATTRIBUTES: [&str; 3] = ["arg", "command", "clap"];
+
+/// Members with these keys are not arguments of their own
+const NOT_ARGUMENTS: [&str; 3] = ["skip", "flatten", "subcommand"];
+
+/// Make the `Field` arguments of a struct, or of each variant of an enum, three-state arguments
+pub(crate) fn clap_optional_fields(input: TokenStream) -> Result<TokenStream, Error> {
+    let mut item = syn::parse2::<Item>(input)?;
+    match &mut item {
+        Item::Struct(item) => add_field_args(&mut item.attrs, &item.fields)?,
+        Item::Enum(item) => {
+            for variant in &mut item.variants {
+                add_field_args(&mut variant.attrs, &variant.fields)?;
+            }
+        }
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                "The attribute can only be applied to struct or enum definitions.",
+            ))
+        }
+    }
+    Ok(quote!(#item))
+}
+
+/// Add `#[command(mut_arg(...))]` applying `field_arg` to each `Field` argument and
+/// `#[command(arg(...))]` adding the clear flags of those with a long name
+fn add_field_args(attrs: &mut Vec<Attribute>, fields: &Fields) -> Result<(), Error> {
+    for field in fields {
+        if !matches!(&field.ty, Type::Path(path) if is_field(&path.path)) || !is_argument(field) {
+            continue;
+        }
+        reject_default_value(field)?;
+        let id = match argument_id(field)? {
+            Some(id) => id,
+            None => continue,
+        };
+        attrs.push(parse_quote!(
+            #[command(mut_arg(#id, optional_field::clap::field_arg))]
+        ));
+        if let Some(long) = long_name(field)? {
+            attrs.push(parse_quote!(
+                #[command(arg(optional_field::clap::clear_flag(#id, #long)))]
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Determine if clap adds an argument for the member, rather than skipping or flattening it
+fn is_argument(field: &Field) -> bool {
+    !clap_entries(field).any(|entry| {
+        matches!(entry.first(), Some(TokenTree::Ident(ident)) if NOT_ARGUMENTS.iter().any(|key| ident == key))
+    })
+}
+
+/// Reject a default value of a `Field` argument, which `field_arg` replaces with its own
+fn reject_default_value(field: &Field) -> Result<(), Error> {
+    for entry in clap_entries(field) {
+        if let Some(TokenTree::Ident(ident)) = entry.first() {
+            if ident.to_string().starts_with("default_value") {
+                return Err(Error::new_spanned(
+                    ident,
+                    "a `Field` argument is `Missing` when not given and cannot have a default \
+                        value, use `Field::unwrap_or` after parsing instead",
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Return the id clap gives the argument of a named member: `#[arg(id = "...")]` or its name
+fn argument_id(field: &Field) -> Result<Option<LitStr>, Error> {
+    for entry in clap_entries(field) {
+        if let [TokenTree::Ident(ident), TokenTree::Punct(punct), value @ ..] = entry.as_slice() {
+            if ident == "id" && punct.as_char() == '=' {
+                return syn::parse2::<LitStr>(value.iter().cloned().collect()).map(Some);
+            }
+        }
+    }
+    Ok(field
+        .ident
+        .as_ref()
+        .map(|ident| LitStr::new(&ident.unraw().to_string(), ident.span())))
+}
+
+/// Return the long name of the argument: `#[arg(long = "...")]` or, for `#[arg(long)]`, the
+/// kebab-cased member name
+fn long_name(field: &Field) -> Result<Option<LitStr>, Error> {
+    for entry in clap_entries(field) {
+        match entry.as_slice() {
+            [TokenTree::Ident(ident)] if ident == "long" => {
+                return Ok(field.ident.as_ref().map(|ident| {
+                    let name = ident.unraw().to_string();
+                    let words = name.split('_').filter(|word| !word.is_empty());
+                    LitStr::new(&words.collect::<Vec<_>>().join("-"), ident.span())
+                }));
+            }
+            [TokenTree::Ident(ident), TokenTree::Punct(punct), value @ ..]
+                if ident == "long" && punct.as_char() == '=' =>
+            {
+                let value = value.iter().cloned().collect::<TokenStream>();
+                return syn::parse2::<LitStr>(value.clone()).map(Some).map_err(|_| {
+                    Error::new_spanned(
+                        value,
+                        "`clap_optional_fields` needs the long name as a string literal, to name \
+                            the `--no-...` flag clearing the argument",
+                    )
+                });
+            }
+            _ => {}
+        }
+    }
+    Ok(None)
+}
+
+/// Return the comma separated entries of the member's clap attributes, such as `long` or
+/// `value_parser = ...`
+fn clap_entries(field: &Field) -> impl Iterator<Item = Vec<TokenTree>> + '_ {
+    field
+        .attrs
+        .iter()
+        .filter(|attr| CLAP_ATTRIBUTES.iter().any(|name| attr.path.is_ident(name)))
+        .flat_map(|attr| attr.tokens.clone())
+        .flat_map(|tree| match tree {
+            TokenTree::Group(group) => {
+                let mut entries = vec![Vec::new()];
+                for tree in group.stream() {
+                    match tree {
+                        TokenTree::Punct(punct) if punct.as_char() == ',' => {
+                            entries.push(Vec::new())
+                        }
+                        tree => entries.last_mut().expect("never empty").push(tree),
+                    }
+                }
+                entries
+            }
+            _ => Vec::new(),
+        })
+}
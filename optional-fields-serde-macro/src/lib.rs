@@ -1,61 +1,291 @@
 extern crate proc_macro;
 
+mod attrs;
+mod changeset;
+mod clap;
+mod model;
+mod paths;
+mod sql;
 mod util;
+mod validate;
 
 use proc_macro::TokenStream;
+use proc_macro2::TokenTree;
 use quote::quote;
-use syn::{parse::Parser, Attribute, Field, Meta, NestedMeta, Path, Type};
+use syn::{
+    parse::Parser, parse_macro_input, Attribute, AttributeArgs, DeriveInput, Field,
+    GenericArgument, Meta, NestedMeta, Path, PathArguments, Type,
+};
 
-use util::apply_function_to_struct_and_enum_fields;
+use util::{apply_function_to_struct_and_enum_fields, Derives, Layout};
 
 /// Add `skip_serializing_if = "Field::is_missing"` and `default` annotations to [`optional_field::Field`] fields.
 ///
 /// The attribute can be added to structs and enums.
 ///
+/// Skipping a member of a tuple struct or tuple variant would shift the members after it, so
+/// `Field` members of tuples with more than one member are rejected, unless they have a `with`,
+/// `serialize_with` or `skip_serializing_if` attribute of their own. With
+/// `#[serde_optional_fields(positional)]` they get
+/// `#[serde(with = "optional_field::serde::positional")]` instead, which writes every member.
+/// `Field` members cannot be `#[serde(flatten)]`ed, as they would have no key of their own.
+///
 /// Import this attribute with `use optional_field::serde_optional_fields;`.
 ///
 #[proc_macro_attribute]
-pub fn serde_optional_fields(_args: TokenStream, input: TokenStream) -> TokenStream {
-    let res = match apply_function_to_struct_and_enum_fields(input, add_serde_optional_fields) {
+pub fn serde_optional_fields(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as AttributeArgs);
+    let positional = match positional_arg(&args) {
+        Ok(positional) => positional,
+        Err(err) => return TokenStream::from(err.to_compile_error()),
+    };
+    let derives = Derives::from_input(&input);
+    let res = match apply_function_to_struct_and_enum_fields(input, |field, layout| {
+        add_serde_optional_fields(field, layout, positional, &derives)
+    }) {
+        Ok(res) => res,
+        Err(err) => err.to_compile_error(),
+    };
+    TokenStream::from(res)
+}
+
+/// Make the `Field` members of a struct deriving clap's `Parser` or `Args` three-state arguments.
+///
+/// Each `Field` argument is `Missing` when it is not given, and arguments with a long name can be
+/// cleared with a `--no-<long>` flag. See the `optional_field::clap` module.
+///
+/// The attribute adds `#[command(mut_arg(...))]` and `#[command(arg(...))]` attributes to the item,
+/// so it must come before `#[derive(Parser)]`. A long name must be given as a string literal, and
+/// `Field` members cannot have a `default_value`, as `Missing` is read from a default of its own.
+/// Variants of enums deriving `Subcommand` are handled like structs.
+///
+/// Import this attribute with `use optional_field::clap_optional_fields;`.
+#[proc_macro_attribute]
+pub fn clap_optional_fields(_args: TokenStream, input: TokenStream) -> TokenStream {
+    let res = match clap::clap_optional_fields(input.into()) {
+        Ok(res) => res,
+        Err(err) => err.to_compile_error(),
+    };
+    TokenStream::from(res)
+}
+
+/// Derive `optional_field::validation::ValidateFields` from `#[field(...)]` member attributes.
+///
+/// See the `optional_field::validation` module for the supported rules.
+///
+/// The `#[field(...)]` attribute is shared by all derives of this crate, each of which accepts
+/// the keys of all of them and rejects any other key.
+#[proc_macro_derive(ValidateFields, attributes(field))]
+pub fn derive_validate_fields(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let res = match validate::derive_validate_fields(&input) {
+        Ok(res) => res,
+        Err(err) => err.to_compile_error(),
+    };
+    TokenStream::from(res)
+}
+
+/// Derive `TryFrom<Patch> for Model` for each `#[field(model = "Model")]` of a patch struct.
+///
+/// `Field` members are converted with `optional_field::validation::FromField`, all other members
+/// are moved as they are. Every missing or null member is reported at once.
+#[proc_macro_derive(TryIntoModel, attributes(field))]
+pub fn derive_try_into_model(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let res = match model::derive_try_into_model(&input) {
+        Ok(res) => res,
+        Err(err) => err.to_compile_error(),
+    };
+    TokenStream::from(res)
+}
+
+/// Derive `optional_field::mask::PresentPaths`, listing the wire paths of present `Field` members.
+///
+/// Members marked `#[field(nested)]` are recursed into, members that are neither `Field` nor
+/// `nested` are ignored.
+#[proc_macro_derive(PresentPaths, attributes(field))]
+pub fn derive_present_paths(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let res = match paths::derive_present_paths(&input) {
+        Ok(res) => res,
+        Err(err) => err.to_compile_error(),
+    };
+    TokenStream::from(res)
+}
+
+/// Derive diesel's `AsChangeset` for references to a patch struct, so it can be passed to
+/// `diesel::update(...).set(&patch)`.
+///
+/// The table is given with `#[field(table = "...")]`, columns are named like the members unless
+/// overridden with `#[field(column = "...")]`. `Field` members are left out when `Missing` and set
+/// to `NULL` when `Present(None)`, `Option` members are left out when `None`.
+#[proc_macro_derive(FieldChangeset, attributes(field))]
+pub fn derive_field_changeset(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let res = match changeset::derive_field_changeset(&input) {
+        Ok(res) => res,
+        Err(err) => err.to_compile_error(),
+    };
+    TokenStream::from(res)
+}
+
+/// Derive `optional_field::sql::SqlPatch`, assigning present `Field` members to columns.
+///
+/// The column is named like the member, unless overridden with `#[field(column = "...")]`.
+/// Members that are not `Field` are ignored.
+#[proc_macro_derive(SqlPatch, attributes(field))]
+pub fn derive_sql_patch(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let res = match sql::derive_sql_patch(&input) {
         Ok(res) => res,
         Err(err) => err.to_compile_error(),
     };
     TokenStream::from(res)
 }
 
+/// Read the arguments of `#[serde_optional_fields(...)]`, returning whether `positional` is given
+fn positional_arg(args: &[NestedMeta]) -> Result<bool, syn::Error> {
+    let mut positional = false;
+    for arg in args {
+        match arg {
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("positional") => positional = true,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    arg,
+                    "unknown argument, expected `positional`",
+                ))
+            }
+        }
+    }
+    Ok(positional)
+}
+
 /// Add the skip_serializing_if annotation to each field of the struct
-fn add_serde_optional_fields(field: &mut Field) -> Result<(), String> {
+///
+/// Members of tuples laid out as [`Layout::Tuple`] get the positional encoding instead if
+/// `positional` is given, see [`add_positional_encoding`], and are rejected otherwise.
+/// Flattened members are rejected.
+/// Members of items deriving `utoipa::ToSchema` additionally get
+/// `#[schema(value_type = Option<T>)]`, so they are documented like `Option` members.
+fn add_serde_optional_fields(
+    field: &mut Field,
+    layout: Layout,
+    positional: bool,
+    derives: &Derives,
+) -> Result<(), String> {
     if let Type::Path(path) = &field.ty {
         if is_field(&path.path) {
+            if field_has_attribute(field, "serde", "flatten") {
+                return Err(
+                    "a flattened `Field` has no key of its own to tell `Missing` from \
+                    `null`, flatten a struct with `Field` members instead"
+                        .to_string(),
+                );
+            }
+            if layout == Layout::Tuple {
+                if has_own_encoding(field) {
+                    return Ok(());
+                }
+                if !positional {
+                    return Err(
+                        "skipping a `Missing` member of a tuple would shift the members after \
+                        it, use `#[serde_optional_fields(positional)]` to write `Field` members \
+                        as `[]`, `[null]` or `[value]`"
+                            .to_string(),
+                    );
+                }
+                add_positional_encoding(field);
+                return Ok(());
+            }
             let has_skip_serializing_if =
                 field_has_attribute(field, "serde", "skip_serializing_if");
             let has_default = field_has_attribute(field, "serde", "default");
+            let inner_ty = field_inner_type(&path.path).cloned();
 
             if !has_skip_serializing_if {
-                let attr_tokens = quote!(
-                    #[serde(skip_serializing_if = "optional_field::Field::is_missing")]
+                // Put it first: specta reads `skip_serializing_if` as "not optional" unless a
+                // `default` follows it
+                add_attribute_first(
+                    field,
+                    quote!(
+                        #[serde(skip_serializing_if = "optional_field::Field::is_missing")]
+                    ),
                 );
-                let parser = Attribute::parse_outer;
-                let attrs = parser
-                    .parse2(attr_tokens)
-                    .expect("Static attr tokens should not panic");
-                field.attrs.extend(attrs);
             }
             if !has_default {
-                let attr_tokens = quote!(
-                    #[serde(default)]
+                add_attribute(
+                    field,
+                    quote!(
+                        #[serde(default)]
+                    ),
                 );
-                let parser = Attribute::parse_outer;
-                let attrs = parser
-                    .parse2(attr_tokens)
-                    .expect("Static attr tokens should not panic");
-                field.attrs.extend(attrs);
+            }
+            if let (true, Some(inner_ty)) = (derives.to_schema, inner_ty) {
+                if !field_has_attribute_key(field, "schema", "value_type") {
+                    add_attribute(
+                        field,
+                        quote!(
+                            #[schema(value_type = Option<#inner_ty>)]
+                        ),
+                    );
+                }
             }
         }
     }
     Ok(())
 }
 
+/// Determine if the serialization of a member is already customized
+fn has_own_encoding(field: &Field) -> bool {
+    [
+        "with",
+        "serialize_with",
+        "deserialize_with",
+        "skip_serializing_if",
+    ]
+    .iter()
+    .any(|name| field_has_attribute(field, "serde", name))
+}
+
+/// Add `with = "optional_field::serde::positional"` to a member of a tuple
+fn add_positional_encoding(field: &mut Field) {
+    add_attribute(
+        field,
+        quote!(
+            #[serde(with = "optional_field::serde::positional")]
+        ),
+    );
+}
+
+/// Parse the static attribute `attr_tokens` and add it to `field`
+fn add_attribute(field: &mut Field, attr_tokens: proc_macro2::TokenStream) {
+    let parser = Attribute::parse_outer;
+    let attrs = parser
+        .parse2(attr_tokens)
+        .expect("Static attr tokens should not panic");
+    field.attrs.extend(attrs);
+}
+
+/// Parse the static attribute `attr_tokens` and add it to `field` before its other attributes
+fn add_attribute_first(field: &mut Field, attr_tokens: proc_macro2::TokenStream) {
+    let parser = Attribute::parse_outer;
+    let attrs = parser
+        .parse2(attr_tokens)
+        .expect("Static attr tokens should not panic");
+    field.attrs.splice(0..0, attrs);
+}
+
+/// Return `T` of a `Field<T>` type path
+fn field_inner_type(path: &Path) -> Option<&Type> {
+    match &path.segments.last()?.arguments {
+        PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
 /// Return `true`, if the type path refers to `optional_field::Field`
 ///
 /// Accepts
@@ -76,7 +306,7 @@ fn is_field(path: &Path) -> bool {
 ///
 /// * `serde` is the outermost path, here namespace
 /// * it contains a Meta::List
-/// * which contains in another Meta a Meta::NameValue
+/// * which contains in another Meta a Meta::NameValue (or a Meta::Path for `default`)
 /// * with the name being `skip_serializing_if`
 fn field_has_attribute(field: &Field, namespace: &str, name: &str) -> bool {
     for attr in &field.attrs {
@@ -84,12 +314,14 @@ fn field_has_attribute(field: &Field, namespace: &str, name: &str) -> bool {
             // Ignore non parsable attributes, as these are not important for us
             if let Ok(Meta::List(expr)) = attr.parse_meta() {
                 for expr in expr.nested {
-                    if let NestedMeta::Meta(Meta::NameValue(expr)) = expr {
-                        if let Some(ident) = expr.path.get_ident() {
-                            if *ident == name {
-                                return true;
-                            }
-                        }
+                    let path = match expr {
+                        NestedMeta::Meta(Meta::NameValue(expr)) => expr.path,
+                        // e.g. `#[serde(default)]`
+                        NestedMeta::Meta(Meta::Path(path)) => path,
+                        _ => continue,
+                    };
+                    if path.is_ident(name) {
+                        return true;
                     }
                 }
             }
@@ -97,3 +329,28 @@ fn field_has_attribute(field: &Field, namespace: &str, name: &str) -> bool {
     }
     false
 }
+
+/// Determine if the `field` has an attribute `#[namespace(...)]` containing `name = ...`
+///
+/// Unlike [`field_has_attribute`] the value can be any tokens, such as the type in
+/// `#[schema(value_type = Option<u8>)]`.
+fn field_has_attribute_key(field: &Field, namespace: &str, name: &str) -> bool {
+    field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident(namespace))
+        .any(|attr| {
+            attr.tokens.clone().into_iter().any(|tree| match tree {
+                TokenTree::Group(group) => {
+                    let trees = group.stream().into_iter().collect::<Vec<_>>();
+                    trees.windows(2).any(|pair| match pair {
+                        [TokenTree::Ident(ident), TokenTree::Punct(punct)] => {
+                            ident == name && punct.as_char() == '='
+                        }
+                        _ => false,
+                    })
+                }
+                _ => false,
+            })
+        })
+}
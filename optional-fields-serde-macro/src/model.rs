@@ -0,0 +1,70 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{spanned::Spanned, DeriveInput, Error, Type};
+
+use crate::attrs::{serde_name_value, wire_name, ContainerAttrs};
+use crate::is_field;
+use crate::util::named_fields;
+
+/// Generate `TryFrom<Patch> for Model` for every `#[field(model = "Model")]` of a patch struct
+pub(crate) fn derive_try_into_model(input: &DeriveInput) -> Result<TokenStream, Error> {
+    let fields = named_fields(input, "TryIntoModel")?;
+    let container = ContainerAttrs::from_attrs(&input.attrs)?;
+    if container.models.is_empty() {
+        return Err(Error::new(
+            Span::call_site(),
+            "`TryIntoModel` requires at least one `#[field(model = \"...\")]` attribute",
+        ));
+    }
+    let rename_all = serde_name_value(&input.attrs, "rename_all");
+
+    let mut idents = Vec::new();
+    let mut vars = Vec::new();
+    let mut conversions = Vec::new();
+    for (i, field) in fields.iter().enumerate() {
+        // Parsed for the error messages only, the conversion does not depend on any option
+        container.field_attrs(&field.attrs)?;
+        let ident = field.ident.as_ref().expect("named fields have idents");
+        let name = wire_name(&ident.to_string(), &field.attrs, rename_all.as_deref());
+        let var = format_ident!("field_{}", i);
+
+        let conversion = if matches!(&field.ty, Type::Path(path) if is_field(&path.path)) {
+            quote_spanned! {field.span()=>
+                let #var = match optional_field::validation::FromField::from_field(patch.#ident) {
+                    Ok(val) => Some(val),
+                    Err(violation) => {
+                        errors.push(#name, violation);
+                        None
+                    }
+                };
+            }
+        } else {
+            quote!(let #var = Some(patch.#ident);)
+        };
+        idents.push(ident);
+        vars.push(var);
+        conversions.push(conversion);
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let impls = container.models.iter().map(|model| {
+        quote! {
+            impl #impl_generics std::convert::TryFrom<#ident #ty_generics> for #model #where_clause {
+                type Error = optional_field::validation::ValidationErrors;
+
+                fn try_from(patch: #ident #ty_generics) -> Result<Self, Self::Error> {
+                    let mut errors = optional_field::validation::ValidationErrors::new();
+                    #(#conversions)*
+                    match (#(#vars,)*) {
+                        (#(Some(#vars),)*) => Ok(#model {
+                            #(#idents: #vars,)*
+                        }),
+                        _ => Err(errors),
+                    }
+                }
+            }
+        }
+    });
+    Ok(quote!(#(#impls)*))
+}
@@ -0,0 +1,71 @@
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::{spanned::Spanned, DeriveInput, Error, Type};
+
+use crate::attrs::{serde_name_value, wire_name, ContainerAttrs};
+use crate::is_field;
+use crate::util::named_fields;
+
+/// Generate the `PresentPaths` implementation for a struct
+///
+/// `Field` members are listed by their own path, `nested` members are recursed into and all
+/// other members are ignored.
+pub(crate) fn derive_present_paths(input: &DeriveInput) -> Result<TokenStream, Error> {
+    let fields = named_fields(input, "PresentPaths")?;
+    let container = ContainerAttrs::from_attrs(&input.attrs)?;
+    let rename_all = serde_name_value(&input.attrs, "rename_all");
+
+    let mut pushes = Vec::new();
+    let mut restricts = Vec::new();
+    for field in fields {
+        let attrs = container.field_attrs(&field.attrs)?;
+        let ident = field.ident.as_ref().expect("named fields have idents");
+        let name = wire_name(&ident.to_string(), &field.attrs, rename_all.as_deref());
+
+        if attrs.nested {
+            pushes.push(quote_spanned! {field.span()=>
+                optional_field::mask::PresentPaths::present_paths_at(
+                    &self.#ident,
+                    &optional_field::validation::join_path(path, #name),
+                    paths,
+                );
+            });
+            restricts.push(quote_spanned! {field.span()=>
+                optional_field::mask::PresentPaths::restrict_to_at(
+                    &mut self.#ident,
+                    &optional_field::validation::join_path(path, #name),
+                    mask,
+                );
+            });
+        } else if matches!(&field.ty, Type::Path(path) if is_field(&path.path)) {
+            pushes.push(quote_spanned! {field.span()=>
+                optional_field::mask::push_present(
+                    &self.#ident,
+                    optional_field::validation::join_path(path, #name),
+                    paths,
+                );
+            });
+            restricts.push(quote_spanned! {field.span()=>
+                optional_field::mask::restrict(
+                    &mut self.#ident,
+                    &optional_field::validation::join_path(path, #name),
+                    mask,
+                );
+            });
+        }
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics optional_field::mask::PresentPaths for #ident #ty_generics #where_clause {
+            fn present_paths_at(&self, path: &str, paths: &mut Vec<String>) {
+                #(#pushes)*
+            }
+
+            fn restrict_to_at(&mut self, path: &str, mask: &[&str]) {
+                #(#restricts)*
+            }
+        }
+    })
+}
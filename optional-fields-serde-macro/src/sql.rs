@@ -0,0 +1,49 @@
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::{spanned::Spanned, DeriveInput, Error, LitStr, Type};
+
+use crate::attrs::ContainerAttrs;
+use crate::is_field;
+use crate::util::named_fields;
+
+/// Generate the `SqlPatch` implementation for a struct
+///
+/// Only `Field` members are assigned, to the column named like the member unless overridden
+/// with `#[field(column = "...")]`.
+pub(crate) fn derive_sql_patch(input: &DeriveInput) -> Result<TokenStream, Error> {
+    let fields = named_fields(input, "SqlPatch")?;
+    let container = ContainerAttrs::from_attrs(&input.attrs)?;
+
+    let mut pushes = Vec::new();
+    for field in fields {
+        let attrs = container.field_attrs(&field.attrs)?;
+        let ident = field.ident.as_ref().expect("named fields have idents");
+
+        if !matches!(&field.ty, Type::Path(path) if is_field(&path.path)) {
+            if let Some(column) = &attrs.column {
+                return Err(Error::new_spanned(
+                    column,
+                    "`column` can only be applied to `Field` members",
+                ));
+            }
+            continue;
+        }
+        let column = attrs.column.unwrap_or_else(|| {
+            let name = ident.to_string();
+            LitStr::new(name.trim_start_matches("r#"), ident.span())
+        });
+        pushes.push(quote_spanned! {field.span()=>
+            assignments.extend(optional_field::sql::Assignment::from_field(#column, &self.#ident));
+        });
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics optional_field::sql::SqlPatch for #ident #ty_generics #where_clause {
+            fn assignments_into(&self, assignments: &mut Vec<optional_field::sql::Assignment>) {
+                #(#pushes)*
+            }
+        }
+    })
+}
@@ -3,7 +3,8 @@ use proc_macro2::Span;
 use quote::quote;
 use std::iter::Iterator;
 use syn::Error;
-use syn::{spanned::Spanned, Field, Fields, ItemEnum, ItemStruct};
+use syn::{punctuated::Punctuated, spanned::Spanned, token::Comma};
+use syn::{Data, DataStruct, DeriveInput, Field, Fields, ItemEnum, ItemStruct, Meta, NestedMeta};
 
 /// Merge multiple [`syn::Error`] into one.
 pub(crate) trait IteratorExt {
@@ -24,6 +25,51 @@ pub(crate) trait IteratorExt {
 }
 impl<I> IteratorExt for I where I: Iterator<Item = Result<(), Error>> + Sized {}
 
+/// Derives of the annotated item which `serde_optional_fields` cooperates with
+#[derive(Clone, Copy, Default)]
+pub(crate) struct Derives {
+    /// `utoipa::ToSchema`
+    pub to_schema: bool,
+}
+
+impl Derives {
+    /// Look at the `#[derive(...)]` attributes following the attribute macro
+    pub(crate) fn from_input(input: &TokenStream) -> Self {
+        let mut res = Derives::default();
+        if let Ok(input) = syn::parse::<DeriveInput>(input.clone()) {
+            for attr in input
+                .attrs
+                .iter()
+                .filter(|attr| attr.path.is_ident("derive"))
+            {
+                if let Ok(Meta::List(list)) = attr.parse_meta() {
+                    for derive in list.nested {
+                        if let NestedMeta::Meta(Meta::Path(path)) = derive {
+                            match path.segments.last() {
+                                Some(last) if last.ident == "ToSchema" => res.to_schema = true,
+                                _ => {}
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        res
+    }
+}
+
+/// How the members of a struct or enum variant are laid out
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Layout {
+    /// Members with names, serialized as a map
+    Named,
+    /// A single unnamed member, serialized as the member itself
+    Newtype,
+    /// Several unnamed members, serialized as a sequence where skipping a member shifts the
+    /// position of the ones after it
+    Tuple,
+}
+
 /// Apply function on every field of structs or enums
 pub(crate) fn apply_function_to_struct_and_enum_fields<F>(
     input: TokenStream,
@@ -31,12 +77,12 @@ pub(crate) fn apply_function_to_struct_and_enum_fields<F>(
 ) -> Result<proc_macro2::TokenStream, Error>
 where
     F: Copy,
-    F: Fn(&mut Field) -> Result<(), String>,
+    F: Fn(&mut Field, Layout) -> Result<(), String>,
 {
     /// Handle a single struct or a single enum variant
     fn apply_on_fields<F>(fields: &mut Fields, function: F) -> Result<(), Error>
     where
-        F: Fn(&mut Field) -> Result<(), String>,
+        F: Fn(&mut Field, Layout) -> Result<(), String>,
     {
         match fields {
             // simple, no fields, do nothing
@@ -44,13 +90,24 @@ where
             Fields::Named(ref mut fields) => fields
                 .named
                 .iter_mut()
-                .map(|field| function(field).map_err(|err| Error::new(field.span(), err)))
-                .collect_error(),
-            Fields::Unnamed(ref mut fields) => fields
-                .unnamed
-                .iter_mut()
-                .map(|field| function(field).map_err(|err| Error::new(field.span(), err)))
+                .map(|field| {
+                    function(field, Layout::Named).map_err(|err| Error::new(field.span(), err))
+                })
                 .collect_error(),
+            Fields::Unnamed(ref mut fields) => {
+                let layout = if fields.unnamed.len() == 1 {
+                    Layout::Newtype
+                } else {
+                    Layout::Tuple
+                };
+                fields
+                    .unnamed
+                    .iter_mut()
+                    .map(|field| {
+                        function(field, layout).map_err(|err| Error::new(field.span(), err))
+                    })
+                    .collect_error()
+            }
         }
     }
 
@@ -73,3 +130,23 @@ where
         ))
     }
 }
+
+/// Return the named fields of a struct, or an error naming the `derive` for anything else
+pub(crate) fn named_fields<'a>(
+    input: &'a DeriveInput,
+    derive: &str,
+) -> Result<&'a Punctuated<Field, Comma>, Error> {
+    match &input.data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(fields),
+            ..
+        }) => Ok(&fields.named),
+        _ => Err(Error::new(
+            Span::call_site(),
+            format!(
+                "`{}` can only be derived for structs with named fields",
+                derive
+            ),
+        )),
+    }
+}
@@ -0,0 +1,98 @@
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::{spanned::Spanned, DeriveInput, Error, Type};
+
+use crate::attrs::{serde_name_value, wire_name, ContainerAttrs};
+use crate::is_field;
+use crate::util::named_fields;
+
+/// Generate the `ValidateFields` implementation for a struct
+pub(crate) fn derive_validate_fields(input: &DeriveInput) -> Result<TokenStream, Error> {
+    let fields = named_fields(input, "ValidateFields")?;
+    let container = ContainerAttrs::from_attrs(&input.attrs)?;
+    let rename_all = serde_name_value(&input.attrs, "rename_all");
+
+    let mut checks = Vec::new();
+    for field in fields {
+        let attrs = container.field_attrs(&field.attrs)?;
+        let ident = field.ident.as_ref().expect("named fields have idents");
+        let name = wire_name(&ident.to_string(), &field.attrs, rename_all.as_deref());
+
+        if attrs.has_rules() {
+            if !matches!(&field.ty, Type::Path(path) if is_field(&path.path)) {
+                return Err(Error::new(
+                    field.ty.span(),
+                    "validation rules can only be applied to `Field` members",
+                ));
+            }
+            let mut rules = Vec::new();
+            if attrs.required {
+                rules.push(quote!(optional_field::validation::required()));
+            }
+            if !attrs.required_on.is_empty() {
+                let modes = &attrs.required_on;
+                rules.push(quote!(optional_field::validation::required_on(
+                    &[#(optional_field::validation::Mode::#modes),*],
+                    mode,
+                )));
+            }
+            if attrs.non_null {
+                rules.push(quote!(optional_field::validation::non_null()));
+            }
+            if attrs.non_empty {
+                rules.push(quote!(optional_field::validation::non_empty()));
+            }
+            if attrs.len_min.is_some() || attrs.len_max.is_some() {
+                let min = option_tokens(&attrs.len_min);
+                let max = option_tokens(&attrs.len_max);
+                rules.push(quote!(optional_field::validation::len(#min, #max)));
+            }
+            for custom in &attrs.custom {
+                rules.push(quote!(#custom));
+            }
+            let rule = rules
+                .into_iter()
+                .reduce(|a, b| quote!(optional_field::validation::RuleExt::and(#a, #b)))
+                .expect("has_rules checked there is at least one rule");
+            checks.push(quote_spanned! {field.span()=>
+                errors.check(
+                    &optional_field::validation::join_path(path, #name),
+                    &self.#ident,
+                    &#rule,
+                );
+            });
+        }
+        if attrs.nested {
+            checks.push(quote_spanned! {field.span()=>
+                optional_field::validation::ValidateFields::validate_fields_at(
+                    &self.#ident,
+                    mode,
+                    &optional_field::validation::join_path(path, #name),
+                    errors,
+                );
+            });
+        }
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics optional_field::validation::ValidateFields for #ident #ty_generics #where_clause {
+            fn validate_fields_at(
+                &self,
+                mode: Option<optional_field::validation::Mode>,
+                path: &str,
+                errors: &mut optional_field::validation::ValidationErrors,
+            ) {
+                #(#checks)*
+            }
+        }
+    })
+}
+
+fn option_tokens(expr: &Option<syn::Expr>) -> TokenStream {
+    match expr {
+        Some(expr) => quote!(Some(#expr)),
+        None => quote!(None),
+    }
+}
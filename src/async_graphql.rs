@@ -0,0 +1,69 @@
+use std::borrow::Cow;
+
+use async_graphql::registry::Registry;
+use async_graphql::{InputType, InputValueError, InputValueResult, MaybeUndefined, Value};
+
+use crate::Field::{self, *};
+
+impl<T> From<MaybeUndefined<T>> for Field<T> {
+    fn from(value: MaybeUndefined<T>) -> Field<T> {
+        match value {
+            MaybeUndefined::Undefined => Missing,
+            MaybeUndefined::Null => Present(None),
+            MaybeUndefined::Value(value) => Present(Some(value)),
+        }
+    }
+}
+
+impl<T> From<Field<T>> for MaybeUndefined<T> {
+    fn from(field: Field<T>) -> MaybeUndefined<T> {
+        match field {
+            Missing => MaybeUndefined::Undefined,
+            Present(None) => MaybeUndefined::Null,
+            Present(Some(value)) => MaybeUndefined::Value(value),
+        }
+    }
+}
+
+/// A `Field<T>` is a nullable `T` input, parsed like `MaybeUndefined<T>`: an omitted value is
+/// [`Missing`], `null` is `Present(None)`.
+impl<T: InputType> InputType for Field<T> {
+    type RawValueType = T::RawValueType;
+
+    fn type_name() -> Cow<'static, str> {
+        T::type_name()
+    }
+
+    fn qualified_type_name() -> String {
+        T::type_name().to_string()
+    }
+
+    fn create_type_info(registry: &mut Registry) -> String {
+        T::create_type_info(registry);
+        T::type_name().to_string()
+    }
+
+    fn parse(value: Option<Value>) -> InputValueResult<Self> {
+        match value {
+            None => Ok(Missing),
+            Some(Value::Null) => Ok(Present(None)),
+            Some(value) => Ok(Present(Some(
+                T::parse(Some(value)).map_err(InputValueError::propagate)?,
+            ))),
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        match self {
+            Present(Some(value)) => value.to_value(),
+            _ => Value::Null,
+        }
+    }
+
+    fn as_raw_value(&self) -> Option<&Self::RawValueType> {
+        match self {
+            Present(Some(value)) => value.as_raw_value(),
+            _ => None,
+        }
+    }
+}
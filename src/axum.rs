@@ -0,0 +1,123 @@
+use axum::extract::rejection::JsonRejection;
+use axum::extract::{FromRequest, Request};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::error::Error;
+use std::fmt;
+
+use crate::validation::{Mode, ValidateFields, ValidationErrors};
+
+/// Extractor for the JSON body of a `PATCH` request.
+///
+/// The body is deserialized like with axum's [`Json`] extractor and then validated with
+/// [`ValidateFields::validate_for`] in [`Mode::Update`]. A body that fails validation is rejected
+/// with `422 Unprocessable Entity` and lists every violation by its wire path:
+///
+/// ```json
+/// {"errors": [{"path": "name", "message": "must not be null"}]}
+/// ```
+///
+/// Members that are not known to `T` are only rejected when `T` uses
+/// `#[serde(deny_unknown_fields)]`, in which case axum's own `422` response is returned.
+///
+/// ```
+/// use axum::routing::patch;
+/// use axum::Router;
+/// use optional_field::{serde_optional_fields, Field, Patch, ValidateFields};
+/// use serde::Deserialize;
+///
+/// #[serde_optional_fields]
+/// #[derive(Deserialize, ValidateFields)]
+/// struct UserPatch {
+///     #[field(non_null)]
+///     name: Field<String>,
+///     email: Field<String>,
+/// }
+///
+/// async fn update_user(Patch(patch): Patch<UserPatch>) {
+///     // ...
+/// }
+///
+/// let app: Router = Router::new().route("/users/{id}", patch(update_user));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Patch<T>(pub T);
+
+impl<T, S> FromRequest<S> for Patch<T>
+where
+    T: DeserializeOwned + ValidateFields,
+    S: Send + Sync,
+{
+    type Rejection = PatchRejection;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, state)
+            .await
+            .map_err(PatchRejection::Json)?;
+        value
+            .validate_for(Mode::Update)
+            .map_err(PatchRejection::Invalid)?;
+        Ok(Patch(value))
+    }
+}
+
+/// Rejection used for [`Patch`].
+#[derive(Debug)]
+pub enum PatchRejection {
+    /// The body could not be read or deserialized, responded to like axum's [`Json`] does.
+    Json(JsonRejection),
+    /// The body failed validation, responded to with `422 Unprocessable Entity`.
+    Invalid(ValidationErrors),
+}
+
+impl fmt::Display for PatchRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatchRejection::Json(rejection) => write!(f, "{}", rejection),
+            PatchRejection::Invalid(errors) => write!(f, "{}", errors),
+        }
+    }
+}
+
+impl Error for PatchRejection {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PatchRejection::Json(rejection) => Some(rejection),
+            PatchRejection::Invalid(errors) => Some(errors),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorsBody {
+    errors: Vec<ErrorBody>,
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    path: String,
+    message: String,
+}
+
+impl IntoResponse for PatchRejection {
+    fn into_response(self) -> Response {
+        match self {
+            PatchRejection::Json(rejection) => rejection.into_response(),
+            PatchRejection::Invalid(errors) => {
+                let body = ErrorsBody {
+                    errors: errors
+                        .into_iter()
+                        .map(|error| ErrorBody {
+                            message: error.violation.to_string(),
+                            path: error.path,
+                        })
+                        .collect(),
+                };
+                (StatusCode::UNPROCESSABLE_ENTITY, Json(body)).into_response()
+            }
+        }
+    }
+}
@@ -0,0 +1,200 @@
+//! Command line arguments with three states.
+//!
+//! A `Field<T>` argument is [`Missing`] when it is not given, `Present(None)` when it is cleared
+//! with its `--no-<name>` flag or given the clear value (empty by default, as in `--name=`) and
+//! `Present(Some(_))` otherwise.
+//!
+//! `Field<T>` implements clap's [`ValueParserFactory`] for any `T: FromStr`, so
+//! `#[derive(Parser)]` picks up [`FieldValueParser`] without further attributes. The derive does
+//! however require a value for every member that is not an `Option`, and can only add a single
+//! argument per member. [`clap_optional_fields`](crate::clap_optional_fields) applies
+//! [`field_arg`] to every `Field` member and adds a [`clear_flag`] for those with a long name:
+//!
+//! ```
+//! use clap::{value_parser, Parser};
+//! use optional_field::clap::FieldValueParser;
+//! use optional_field::clap_optional_fields;
+//! use optional_field::Field::{self, *};
+//!
+//! #[clap_optional_fields]
+//! #[derive(Parser)]
+//! struct UpdateUser {
+//!     /// New name
+//!     #[arg(long)]
+//!     name: Field<String>,
+//!     /// New email
+//!     #[arg(long)]
+//!     email: Field<String>,
+//!     /// New age, `--age=none` also clears it
+//!     #[arg(
+//!         long,
+//!         value_parser = FieldValueParser::new(value_parser!(u8)).clear_with("none")
+//!     )]
+//!     age: Field<u8>,
+//! }
+//!
+//! let args = UpdateUser::parse_from(["update-user", "--name=bob", "--no-email", "--age=none"]);
+//! assert_eq!(args.name, Present(Some("bob".to_string())));
+//! assert_eq!(args.email, Present(None));
+//! assert_eq!(args.age, Present(None));
+//!
+//! let args = UpdateUser::parse_from(["update-user", "--name="]);
+//! assert_eq!(args.name, Present(None));
+//! assert_eq!(args.email, Missing);
+//! assert_eq!(args.age, Missing);
+//! ```
+//!
+//! [`field_arg`] meets the derive's requirement with hidden default values: a sentinel that
+//! [`FieldValueParser`] reads as [`Missing`], and another one read as `Present(None)` when the
+//! clear flag is given. Only default values are compared with the sentinels, never values given
+//! on the command line. A `Field` argument therefore cannot have a default value of its own, and
+//! `clap_optional_fields` rejects `default_value` and the like on `Field` members:
+//!
+//! ```compile_fail
+//! # use clap::Parser;
+//! # use optional_field::clap_optional_fields;
+//! # use optional_field::Field;
+//! #[clap_optional_fields]
+//! #[derive(Parser)]
+//! struct UpdateUser {
+//!     #[arg(long, default_value = "bob")]
+//!     name: Field<String>,
+//! }
+//! ```
+
+use clap::builder::{ArgPredicate, PossibleValue, TypedValueParser, ValueParserFactory};
+use clap::parser::ValueSource;
+use clap::{Arg, ArgAction, Command, Error, Id};
+use std::error;
+use std::ffi::OsStr;
+use std::str::FromStr;
+
+use crate::Field::{self, *};
+use crate::{EmptyAs, NullToken};
+
+/// Default value of a [`field_arg`] that is not given, parsed as [`Missing`].
+const NOT_GIVEN: &str = "\0missing";
+
+/// Default value of a [`field_arg`] whose clear flag is given, parsed as `Present(None)`.
+const CLEARED: &str = "\0null";
+
+/// Makes `arg` a three-state argument, which is [`Missing`] when it is not given.
+///
+/// The argument is `Present(None)` when the [`clear_flag`] of its id is given. Both states are
+/// passed to [`FieldValueParser`] as sentinel default values, replacing any default value `arg`
+/// had.
+pub fn field_arg(arg: Arg) -> Arg {
+    let clear = clear_id(arg.get_id().as_str());
+    arg.required(false)
+        .default_value(NOT_GIVEN)
+        .default_value_if(clear, ArgPredicate::Equals("true".into()), CLEARED)
+        .hide_default_value(true)
+}
+
+/// The `--no-<long>` flag clearing the [`field_arg`] `id`, which conflicts with giving it a
+/// value.
+pub fn clear_flag(id: &str, long: &str) -> Arg {
+    Arg::new(clear_id(id))
+        .long(format!("no-{}", long))
+        .help(format!("Clear --{}", long))
+        .action(ArgAction::SetTrue)
+        .conflicts_with(id.to_string())
+}
+
+fn clear_id(id: &str) -> Id {
+    Id::from(format!("no-{}", id))
+}
+
+/// Parses a `Field<T>` using the parser `P` for the `T`.
+///
+/// The clear value is parsed as `Present(None)` and any other value as `Present(Some(_))`. The
+/// default values of a [`field_arg`] are parsed as [`Missing`] and, when cleared, as
+/// `Present(None)`.
+#[derive(Debug, Clone)]
+pub struct FieldValueParser<P> {
+    inner: P,
+    null: NullToken<'static>,
+}
+
+impl<P: TypedValueParser> FieldValueParser<P> {
+    /// Creates a parser using `inner` for present values, clearing on an empty value.
+    pub fn new(inner: P) -> Self {
+        FieldValueParser {
+            inner,
+            null: NullToken::new(""),
+        }
+    }
+
+    /// Sets the value that clears the field, e.g. `"none"` or `"null"`.
+    ///
+    /// An empty value is then passed on to the inner parser.
+    pub fn clear_with(self, clear: &'static str) -> Self {
+        self.null_token(NullToken::new(clear).empty_as(EmptyAs::Value))
+    }
+
+    /// Sets how cleared values are told from present ones, e.g. to also treat an empty value
+    /// as [`Missing`].
+    pub fn null_token(mut self, null: NullToken<'static>) -> Self {
+        self.null = null;
+        self
+    }
+}
+
+impl<P> TypedValueParser for FieldValueParser<P>
+where
+    P: TypedValueParser,
+{
+    type Value = Field<P::Value>;
+
+    fn parse_ref(
+        &self,
+        cmd: &Command,
+        arg: Option<&Arg>,
+        value: &OsStr,
+    ) -> Result<Self::Value, Error> {
+        self.parse_ref_(cmd, arg, value, ValueSource::CommandLine)
+    }
+
+    fn parse_ref_(
+        &self,
+        cmd: &Command,
+        arg: Option<&Arg>,
+        value: &OsStr,
+        source: ValueSource,
+    ) -> Result<Self::Value, Error> {
+        if source == ValueSource::DefaultValue {
+            if value == NOT_GIVEN {
+                return Ok(Missing);
+            }
+            if value == CLEARED {
+                return Ok(Present(None));
+            }
+        }
+        match value.to_str().map(|s| self.null.field(s)) {
+            Some(Missing) => Ok(Missing),
+            Some(Present(None)) => Ok(Present(None)),
+            _ => self
+                .inner
+                .parse_ref_(cmd, arg, value, source)
+                .map(Some)
+                .map(Present),
+        }
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        self.inner.possible_values()
+    }
+}
+
+/// Parses the present value with its [`FromStr`] implementation.
+impl<T> ValueParserFactory for Field<T>
+where
+    T: FromStr + Clone + Send + Sync + 'static,
+    T::Err: Into<Box<dyn error::Error + Send + Sync + 'static>>,
+{
+    type Parser = FieldValueParser<fn(&str) -> Result<T, T::Err>>;
+
+    fn value_parser() -> Self::Parser {
+        FieldValueParser::new(T::from_str)
+    }
+}
@@ -0,0 +1,119 @@
+//! Deserializing CSV cells into [`Field`] members.
+//!
+//! When records are read with headers, such as with `csv::Reader::deserialize`, every cell is
+//! looked up by its column name. For a member of a
+//! [`serde_optional_fields`](crate::serde_optional_fields) struct:
+//!
+//! * a column that is not in the header is [`Missing`](crate::Field::Missing)
+//! * an empty cell is `Present(None)`
+//! * any other cell is `Present(Some(_))`
+//!
+//! The `csv` crate alone can not tell a `NULL` cell from a value. Members using
+//! [`deserialize`] as their `deserialize_with` function also read a `NULL` cell as
+//! `Present(None)`, parsing other cells with the [`FromStr`] implementation of `T`.
+//! [`deserialize_with`] takes another [`NullToken`].
+//!
+//! # Examples
+//!
+//! ```
+//! use optional_field::Field::{self, *};
+//! use optional_field::serde_optional_fields;
+//! use serde::Deserialize;
+//!
+//! #[serde_optional_fields]
+//! #[derive(Deserialize)]
+//! struct UserUpdate {
+//!     id: u32,
+//!     #[serde(deserialize_with = "optional_field::csv::deserialize")]
+//!     name: Field<String>,
+//!     #[serde(deserialize_with = "optional_field::csv::deserialize")]
+//!     age: Field<u8>,
+//!     email: Field<String>,
+//! }
+//!
+//! let data = "id,name,age\n1,bob,\n2,NULL,42\n";
+//! let mut reader = csv::Reader::from_reader(data.as_bytes());
+//! let updates = reader
+//!     .deserialize()
+//!     .collect::<Result<Vec<UserUpdate>, _>>()
+//!     .unwrap();
+//!
+//! assert_eq!(updates[0].name, Present(Some("bob".to_string())));
+//! assert_eq!(updates[0].age, Present(None));
+//! assert_eq!(updates[1].name, Present(None));
+//! assert_eq!(updates[1].age, Present(Some(42)));
+//! assert_eq!(updates[1].email, Missing);
+//! ```
+
+use serde::de::{self, Deserializer, Visitor};
+use std::fmt;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use crate::{EmptyAs, Field, NullToken};
+
+/// The null token of [`deserialize`], where both `NULL` and an empty cell are `Present(None)`.
+pub const NULL: NullToken<'static> = NullToken::new("NULL").empty_as(EmptyAs::Null);
+
+/// Deserializes a cell, where `NULL` and an empty cell are `Present(None)`.
+///
+/// To be used with `#[serde(deserialize_with = "optional_field::csv::deserialize")]`.
+pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Field<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    deserialize_with(deserializer, NULL)
+}
+
+/// Deserializes a cell, using `null` to tell [`Missing`](crate::Field::Missing) and `Present(None)` from a value.
+///
+/// An empty cell is `Present(None)`, like the cells of members without `deserialize_with`,
+/// unless `null` sets otherwise with [`NullToken::empty_as`].
+///
+/// # Examples
+///
+/// ```
+/// # use optional_field::Field;
+/// # use optional_field::NullToken;
+/// # use serde::Deserializer;
+/// fn dash_is_null<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Field<u8>, D::Error> {
+///     optional_field::csv::deserialize_with(deserializer, NullToken::new("-"))
+/// }
+/// ```
+pub fn deserialize_with<'de, D, T>(
+    deserializer: D,
+    null: NullToken<'_>,
+) -> Result<Field<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    deserializer.deserialize_str(CellVisitor {
+        null: null.empty_as_or(EmptyAs::Null),
+        marker: PhantomData,
+    })
+}
+
+struct CellVisitor<'a, T> {
+    null: NullToken<'a>,
+    marker: PhantomData<T>,
+}
+
+impl<'de, 'a, T> Visitor<'de> for CellVisitor<'a, T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    type Value = Field<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a cell")
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<Field<T>, E> {
+        Field::parse_with(s, self.null).map_err(E::custom)
+    }
+}
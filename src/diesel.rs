@@ -0,0 +1,115 @@
+//! Binding [`Field`] values in diesel queries.
+//!
+//! A `Field<T>` is bound like an `Option<T>` to a nullable column, through an expression of this
+//! module, as the one diesel's `AsExpression` derive uses is not part of its public API.
+//!
+//! Patches are passed to `diesel::update(...).set(...)` with `#[derive(FieldChangeset)]` rather
+//! than diesel's own `#[derive(AsChangeset)]`. That derive only leaves out members whose type is
+//! written as `Option<...>`, and assigns every other member, so a [`Missing`] member would fail
+//! to serialize instead of being left out.
+
+use std::marker::PhantomData;
+
+use diesel::backend::Backend;
+use diesel::expression::{
+    is_aggregate, AppearsOnTable, AsExpression, Expression, SelectableExpression,
+    TypedExpressionType, ValidGrouping,
+};
+use diesel::query_builder::{AstPass, QueryFragment, QueryId};
+use diesel::serialize::{self, IsNull, Output, ToSql};
+use diesel::sql_types::{is_nullable, HasSqlType, Nullable, SqlType};
+use diesel::QueryResult;
+
+use crate::Field::{self, *};
+use crate::FieldError;
+
+/// `Present(None)` is written as `NULL`.
+///
+/// A [`Missing`] field has no SQL value, executing a query binding one returns a
+/// `SerializationError` wrapping [`FieldError::Missing`].
+impl<T, ST, DB> ToSql<Nullable<ST>, DB> for Field<T>
+where
+    T: ToSql<ST, DB>,
+    DB: Backend,
+    ST: SqlType<IsNull = is_nullable::NotNull>,
+{
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, DB>) -> serialize::Result {
+        match self {
+            Present(Some(val)) => val.to_sql(out),
+            Present(None) => Ok(IsNull::Yes),
+            Missing => Err(Box::new(FieldError::Missing)),
+        }
+    }
+}
+
+/// A `Field<T>` is an expression for a nullable column, like an `Option<T>`.
+impl<T, ST> AsExpression<Nullable<ST>> for Field<T>
+where
+    ST: SqlType<IsNull = is_nullable::NotNull>,
+    Nullable<ST>: TypedExpressionType,
+{
+    type Expression = Bind<Nullable<ST>, Self>;
+
+    fn as_expression(self) -> Self::Expression {
+        Bind::new(self)
+    }
+}
+
+impl<T, ST> AsExpression<Nullable<ST>> for &Field<T>
+where
+    ST: SqlType<IsNull = is_nullable::NotNull>,
+    Nullable<ST>: TypedExpressionType,
+{
+    type Expression = Bind<Nullable<ST>, Self>;
+
+    fn as_expression(self) -> Self::Expression {
+        Bind::new(self)
+    }
+}
+
+/// A bind parameter of SQL type `ST`, the expression a `Field` is bound as.
+#[derive(Debug, Clone, Copy)]
+pub struct Bind<ST, U> {
+    item: U,
+    sql_type: PhantomData<ST>,
+}
+
+impl<ST, U> Bind<ST, U> {
+    fn new(item: U) -> Self {
+        Bind {
+            item,
+            sql_type: PhantomData,
+        }
+    }
+}
+
+impl<ST, U> Expression for Bind<ST, U>
+where
+    ST: SqlType + TypedExpressionType,
+{
+    type SqlType = ST;
+}
+
+impl<ST, U, DB> QueryFragment<DB> for Bind<ST, U>
+where
+    DB: Backend + HasSqlType<ST>,
+    U: ToSql<ST, DB>,
+{
+    fn walk_ast<'b>(&'b self, mut pass: AstPass<'_, 'b, DB>) -> QueryResult<()> {
+        pass.push_bind_param(&self.item)
+    }
+}
+
+impl<ST: QueryId, U> QueryId for Bind<ST, U> {
+    type QueryId = Bind<ST::QueryId, ()>;
+
+    const HAS_STATIC_QUERY_ID: bool = ST::HAS_STATIC_QUERY_ID;
+}
+
+impl<ST, U, QS> SelectableExpression<QS> for Bind<ST, U> where Bind<ST, U>: AppearsOnTable<QS> {}
+
+impl<ST, U, QS> AppearsOnTable<QS> for Bind<ST, U> where Bind<ST, U>: Expression {}
+
+impl<ST, U, GB> ValidGrouping<GB> for Bind<ST, U> {
+    type IsAggregate = is_aggregate::Never;
+}
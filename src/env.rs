@@ -0,0 +1,397 @@
+//! Loading patches of [`Field`](crate::Field) members from environment variables.
+//!
+//! Every member of a struct is read from the variable named after the prefix and the member
+//! name in upper snake case, as it is named by serde. With a prefix of `APP_` the member
+//! `user_name` is read from `APP_USER_NAME`, as is `userName` when serde renames members to
+//! camel case:
+//!
+//! * an unset variable is [`Missing`]
+//! * an empty variable, or the null token set with [`EnvLoader::null_token`], is
+//!   `Present(None)`; [`EnvLoader::empty_as`] changes what an empty variable is
+//! * any other value is parsed with the [`FromStr`] implementation of the
+//!   member type, e.g. `8080` for a `Field<u16>`
+//!
+//! Members that are not `Field`s or `Option`s fail to load if their variable is unset, unless
+//! they have a serde default. Nested structs and sequences are not supported.
+//!
+//! # Examples
+//!
+//! ```
+//! use optional_field::env::EnvLoader;
+//! use optional_field::serde_optional_fields;
+//! use optional_field::Field::{self, *};
+//! use serde::Deserialize;
+//!
+//! #[serde_optional_fields]
+//! #[derive(Deserialize)]
+//! struct Config {
+//!     host: Field<String>,
+//!     port: Field<u16>,
+//!     log_level: Field<String>,
+//! }
+//!
+//! let config: Config = EnvLoader::new("APP_")
+//!     .null_token("__NULL__")
+//!     .load_from(vec![("APP_PORT", "8080"), ("APP_LOG_LEVEL", "__NULL__")])
+//!     .unwrap();
+//!
+//! assert_eq!(config.host, Missing);
+//! assert_eq!(config.port, Present(Some(8080)));
+//! assert_eq!(config.log_level, Present(None));
+//! ```
+//!
+//! The loaded members can be layered over other sources with
+//! [`TracedField::merge`](crate::TracedField::merge), where a [`Missing`]
+//! member keeps the value of the layer below:
+//!
+//! ```
+//! # use optional_field::env::EnvLoader;
+//! # use optional_field::serde_optional_fields;
+//! # use optional_field::Field::{self, *};
+//! # use serde::Deserialize;
+//! # #[serde_optional_fields]
+//! # #[derive(Deserialize)]
+//! # struct Config {
+//! #     host: Field<String>,
+//! #     port: Field<u16>,
+//! # }
+//! let env: Config = EnvLoader::new("APP_")
+//!     .load_from(vec![("APP_PORT", "8080")])
+//!     .unwrap();
+//!
+//! let host = Present(Some("localhost".to_string()))
+//!     .traced("defaults")
+//!     .merged(env.host.traced("env"));
+//! let port = Present(Some(80)).traced("defaults").merged(env.port.traced("env"));
+//!
+//! assert_eq!(host.source(), Some(&"defaults"));
+//! assert_eq!(port.field(), &Present(Some(8080)));
+//! assert_eq!(port.source(), Some(&"env"));
+//! ```
+
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, MapAccess, Visitor};
+use serde::forward_to_deserialize_any;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::{env, slice};
+
+use crate::Field::{self, *};
+use crate::{EmptyAs, NullToken};
+
+/// Loads structs from environment variables sharing a prefix.
+#[derive(Debug, Clone)]
+pub struct EnvLoader {
+    prefix: String,
+    null_token: Option<String>,
+    empty: EmptyAs,
+}
+
+impl EnvLoader {
+    /// Creates a loader reading variables starting with `prefix`, such as `"APP_"`.
+    pub fn new(prefix: impl Into<String>) -> Self {
+        EnvLoader {
+            prefix: prefix.into(),
+            null_token: None,
+            empty: EmptyAs::Null,
+        }
+    }
+
+    /// Sets the value that is loaded as `Present(None)`, in addition to an empty value.
+    pub fn null_token(mut self, token: impl Into<String>) -> Self {
+        self.null_token = Some(token.into());
+        self
+    }
+
+    /// Sets what an empty variable is loaded as, `Present(None)` by default.
+    ///
+    /// With [`EmptyAs::Missing`] an empty variable is treated as if it was unset, with
+    /// [`EmptyAs::Value`] it is parsed like any other value, e.g. as an empty `String`. An empty
+    /// [`null_token`](EnvLoader::null_token) takes precedence over this.
+    pub fn empty_as(mut self, empty: EmptyAs) -> Self {
+        self.empty = empty;
+        self
+    }
+
+    /// Returns the name of the variable `member` is read from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use optional_field::env::EnvLoader;
+    /// assert_eq!(EnvLoader::new("APP_").var("user_name"), "APP_USER_NAME");
+    /// assert_eq!(EnvLoader::new("APP_").var("apiKey"), "APP_API_KEY");
+    /// assert_eq!(EnvLoader::new("APP_").var("httpURL"), "APP_HTTP_URL");
+    /// ```
+    pub fn var(&self, member: &str) -> String {
+        format!("{}{}", self.prefix, upper_snake_case(member))
+    }
+
+    /// Loads a `T` from the environment of the current process.
+    ///
+    /// Fails if one of the prefixed variables is not valid unicode.
+    pub fn load<T: DeserializeOwned>(&self) -> Result<T, EnvError> {
+        let mut vars = HashMap::new();
+        for (key, value) in env::vars_os() {
+            let key = match key.into_string() {
+                Ok(key) if key.starts_with(&self.prefix) => key,
+                _ => continue,
+            };
+            match value.into_string() {
+                Ok(value) => vars.insert(key, value),
+                Err(_) => return Err(EnvError::new(Some(key), "value is not valid unicode")),
+            };
+        }
+        self.load_vars(&vars)
+    }
+
+    /// Loads a `T` from `vars` instead of the environment.
+    pub fn load_from<T, I, K, V>(&self, vars: I) -> Result<T, EnvError>
+    where
+        T: DeserializeOwned,
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let vars = vars
+            .into_iter()
+            .map(|(key, value)| (key.into(), value.into()))
+            .collect();
+        self.load_vars(&vars)
+    }
+
+    /// Tells the state of `value` without parsing it.
+    fn field<'s>(&self, value: &'s str) -> Field<&'s str> {
+        match &self.null_token {
+            Some(token) => NullToken::new(token).empty_as(self.empty).field(value),
+            None if value.is_empty() => match self.empty {
+                EmptyAs::Missing => Missing,
+                EmptyAs::Null => Present(None),
+                EmptyAs::Value => Present(Some(value)),
+            },
+            None => Present(Some(value)),
+        }
+    }
+
+    fn load_vars<T: DeserializeOwned>(
+        &self,
+        vars: &HashMap<String, String>,
+    ) -> Result<T, EnvError> {
+        T::deserialize(Env { loader: self, vars })
+    }
+}
+
+/// Why a struct could not be loaded by an [`EnvLoader`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvError {
+    var: Option<String>,
+    message: String,
+}
+
+/// Returns `member` in upper snake case, starting a new word where lower case turns to upper
+/// case and before the last capital of an acronym followed by lower case, as in `URLPath`
+fn upper_snake_case(member: &str) -> String {
+    let chars = member.chars().collect::<Vec<_>>();
+    let mut res = String::with_capacity(member.len() + 4);
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '-' {
+            res.push('_');
+            continue;
+        }
+        if c.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if prev.is_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_uppercase() && next_is_lower)
+            {
+                res.push('_');
+            }
+        }
+        res.extend(c.to_uppercase());
+    }
+    res
+}
+
+impl EnvError {
+    fn new(var: Option<String>, message: impl fmt::Display) -> Self {
+        EnvError {
+            var,
+            message: message.to_string(),
+        }
+    }
+
+    /// Returns the variable that failed to load, if the error is about a single variable.
+    pub fn var(&self) -> Option<&str> {
+        self.var.as_deref()
+    }
+}
+
+impl fmt::Display for EnvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.var {
+            Some(var) => write!(f, "`{}` {}", var, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl Error for EnvError {}
+
+impl de::Error for EnvError {
+    fn custom<M: fmt::Display>(msg: M) -> Self {
+        EnvError::new(None, msg)
+    }
+}
+
+struct Env<'a> {
+    loader: &'a EnvLoader,
+    vars: &'a HashMap<String, String>,
+}
+
+impl<'de, 'a> de::Deserializer<'de> for Env<'a> {
+    type Error = EnvError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, EnvError> {
+        Err(de::Error::custom(
+            "only structs can be loaded from the environment",
+        ))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, EnvError> {
+        visitor.visit_map(Members {
+            env: self,
+            fields: fields.iter(),
+            value: None,
+        })
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+struct Members<'a> {
+    env: Env<'a>,
+    fields: slice::Iter<'static, &'static str>,
+    value: Option<(String, &'a str)>,
+}
+
+impl<'de, 'a> MapAccess<'de> for Members<'a> {
+    type Error = EnvError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, EnvError> {
+        for field in &mut self.fields {
+            let var = self.env.loader.var(field);
+            let value = match self.env.vars.get(&var) {
+                Some(value) if !self.env.loader.field(value).is_missing() => value,
+                _ => continue,
+            };
+            self.value = Some((var, value));
+            return seed.deserialize(field.into_deserializer()).map(Some);
+        }
+        Ok(None)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, EnvError> {
+        let (var, value) = self
+            .value
+            .take()
+            .ok_or_else(|| de::Error::custom("value requested before key"))?;
+        seed.deserialize(EnvValue {
+            value,
+            loader: self.env.loader,
+        })
+        .map_err(|err| EnvError::new(Some(var), err.message))
+    }
+}
+
+struct EnvValue<'a> {
+    value: &'a str,
+    loader: &'a EnvLoader,
+}
+
+impl<'a> EnvValue<'a> {
+    fn parse<T>(&self) -> Result<T, EnvError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        self.value.parse().map_err(de::Error::custom)
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, EnvError> {
+                visitor.$visit(self.parse()?)
+            }
+        )*
+    };
+}
+
+impl<'de, 'a> de::Deserializer<'de> for EnvValue<'a> {
+    type Error = EnvError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, EnvError> {
+        visitor.visit_str(self.value)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, EnvError> {
+        match self.loader.field(self.value) {
+            Present(None) => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, EnvError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, EnvError> {
+        visitor.visit_enum(self.value.into_deserializer())
+    }
+
+    deserialize_parsed! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
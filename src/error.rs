@@ -0,0 +1,25 @@
+use std::error::Error;
+use std::fmt;
+
+/// Why a value could not be taken out of a [`Field`](crate::Field).
+///
+/// Returned by the non-panicking counterparts of the unwrapping methods, such as
+/// [`Field::try_unwrap`](crate::Field::try_unwrap).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FieldError {
+    /// The field is `Missing`.
+    Missing,
+    /// The field is `Present(None)`.
+    Null,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldError::Missing => write!(f, "field is missing"),
+            FieldError::Null => write!(f, "field is null"),
+        }
+    }
+}
+
+impl Error for FieldError {}
@@ -0,0 +1,152 @@
+//! GraphQL input and output types for [`Field`] with juniper.
+//!
+//! The public [`GraphQLType`], [`GraphQLValue`] and [`FromInputValue`] traits are enough for a
+//! `Field<T>` to be used by `#[derive(GraphQLInputObject)]`, where omitted members are read as
+//! [`Missing`]. Objects implementing a `#[graphql_interface]` however have the types of their
+//! members checked against the interface at compile time, with the traits of
+//! `juniper::macros::reflect`. These are hidden from juniper's documentation and may change in
+//! any release, so their implementations at the end of this module are why juniper is pinned to
+//! an exact version.
+
+use juniper::macros::reflect::{BaseSubTypes, BaseType, Type, Types, WrappedType};
+use juniper::marker::IsInputType;
+use juniper::meta::MetaType;
+use juniper::{
+    ArcStr, BoxFuture, ExecutionResult, Executor, FromInputValue, GraphQLType, GraphQLValue,
+    GraphQLValueAsync, InputValue, Nullable, Registry, ScalarValue, Selection, ToInputValue, Value,
+};
+
+use crate::Field::{self, *};
+
+impl<T> From<Nullable<T>> for Field<T> {
+    fn from(value: Nullable<T>) -> Field<T> {
+        match value {
+            Nullable::ImplicitNull => Missing,
+            Nullable::ExplicitNull => Present(None),
+            Nullable::Some(value) => Present(Some(value)),
+        }
+    }
+}
+
+impl<T> From<Field<T>> for Nullable<T> {
+    fn from(field: Field<T>) -> Nullable<T> {
+        match field {
+            Missing => Nullable::ImplicitNull,
+            Present(None) => Nullable::ExplicitNull,
+            Present(Some(value)) => Nullable::Some(value),
+        }
+    }
+}
+
+/// A `Field<T>` is a nullable `T`, like `Nullable<T>`.
+impl<S, T> GraphQLType<S> for Field<T>
+where
+    T: GraphQLType<S>,
+    S: ScalarValue,
+{
+    fn name(_: &Self::TypeInfo) -> Option<ArcStr> {
+        None
+    }
+
+    fn meta(info: &Self::TypeInfo, registry: &mut Registry<S>) -> MetaType<S> {
+        registry.build_nullable_type::<T>(info).into_meta()
+    }
+}
+
+impl<S, T> GraphQLValue<S> for Field<T>
+where
+    S: ScalarValue,
+    T: GraphQLValue<S>,
+{
+    type Context = T::Context;
+    type TypeInfo = T::TypeInfo;
+
+    fn type_name(&self, _: &Self::TypeInfo) -> Option<ArcStr> {
+        None
+    }
+
+    fn resolve(
+        &self,
+        info: &Self::TypeInfo,
+        _: Option<&[Selection<S>]>,
+        executor: &Executor<Self::Context, S>,
+    ) -> ExecutionResult<S> {
+        match self {
+            Present(Some(value)) => executor.resolve(info, value),
+            _ => Ok(Value::null()),
+        }
+    }
+}
+
+impl<S, T> GraphQLValueAsync<S> for Field<T>
+where
+    T: GraphQLValueAsync<S>,
+    T::TypeInfo: Sync,
+    T::Context: Sync,
+    S: ScalarValue + Send + Sync,
+{
+    fn resolve_async<'a>(
+        &'a self,
+        info: &'a Self::TypeInfo,
+        _: Option<&'a [Selection<S>]>,
+        executor: &'a Executor<Self::Context, S>,
+    ) -> BoxFuture<'a, ExecutionResult<S>> {
+        Box::pin(async move {
+            let value = match self {
+                Present(Some(value)) => executor.resolve_into_value_async(info, value).await,
+                _ => Value::null(),
+            };
+            Ok(value)
+        })
+    }
+}
+
+/// An omitted input value is [`Missing`], `null` is `Present(None)`.
+impl<S, T: FromInputValue<S>> FromInputValue<S> for Field<T> {
+    type Error = T::Error;
+
+    fn from_input_value(v: &InputValue<S>) -> Result<Self, Self::Error> {
+        match v {
+            InputValue::Null => Ok(Present(None)),
+            v => v.convert().map(|value| Present(Some(value))),
+        }
+    }
+
+    fn from_implicit_null() -> Result<Self, Self::Error> {
+        Ok(Missing)
+    }
+}
+
+impl<S, T> ToInputValue<S> for Field<T>
+where
+    T: ToInputValue<S>,
+{
+    fn to_input_value(&self) -> InputValue<S> {
+        match self {
+            Present(Some(value)) => value.to_input_value(),
+            _ => InputValue::null(),
+        }
+    }
+}
+
+impl<S, T> IsInputType<S> for Field<T>
+where
+    T: IsInputType<S>,
+    S: ScalarValue,
+{
+    fn mark() {
+        T::mark()
+    }
+}
+
+impl<S, T: BaseType<S>> BaseType<S> for Field<T> {
+    const NAME: Type = T::NAME;
+}
+
+impl<S, T: BaseSubTypes<S>> BaseSubTypes<S> for Field<T> {
+    const NAMES: Types = T::NAMES;
+}
+
+impl<S, T: WrappedType<S>> WrappedType<S> for Field<T> {
+    const VALUE: u128 = T::VALUE * 10 + 2;
+}
@@ -1,11 +1,76 @@
 use std::ops::{Deref, DerefMut};
 
 #[cfg(feature = "serde")]
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 #[cfg(feature = "serde")]
 pub use optional_fields_serde_macro::serde_optional_fields;
 
+pub use optional_fields_serde_macro::{PresentPaths, TryIntoModel, ValidateFields};
+
+#[cfg(feature = "clap")]
+pub use optional_fields_serde_macro::clap_optional_fields;
+#[cfg(feature = "diesel")]
+pub use optional_fields_serde_macro::FieldChangeset;
+#[cfg(feature = "sql")]
+pub use optional_fields_serde_macro::SqlPatch;
+
+#[cfg(feature = "async-graphql")]
+mod async_graphql;
+#[cfg(feature = "axum")]
+mod axum;
+#[cfg(feature = "clap")]
+pub mod clap;
+#[cfg(feature = "csv")]
+pub mod csv;
+#[cfg(feature = "diesel")]
+mod diesel;
+#[cfg(feature = "env")]
+pub mod env;
+mod error;
+#[cfg(feature = "juniper")]
+mod juniper;
+pub mod mask;
+#[cfg(feature = "minicbor")]
+mod minicbor;
+mod parse;
+#[cfg(feature = "prost")]
+mod prost;
+#[cfg(feature = "rmp")]
+pub mod rmp;
+#[cfg(feature = "schemars")]
+mod schemars;
+#[cfg(feature = "serde")]
+pub mod serde;
+#[cfg(feature = "specta")]
+mod specta;
+#[cfg(feature = "sql")]
+pub mod sql;
+#[cfg(feature = "sqlx")]
+mod sqlx;
+mod traced;
+#[cfg(feature = "ts-rs")]
+mod ts_rs;
+#[cfg(feature = "utoipa")]
+mod utoipa;
+pub mod validation;
+#[cfg(feature = "yaml")]
+pub mod yaml;
+
+#[cfg(feature = "axum")]
+pub use self::axum::{Patch, PatchRejection};
+pub use error::FieldError;
+
+#[cfg(feature = "prost")]
+pub use prost::FieldMaskBuilder;
+
+pub use mask::PresentPaths;
+pub use parse::{EmptyAs, NullToken};
+#[cfg(feature = "sql")]
+pub use sql::SqlPatch;
+pub use traced::{FieldState, ProvenanceEntry, ProvenanceReport, TracedField};
+pub use validation::ValidateFields;
+
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub enum Field<T> {
     #[default]
@@ -301,6 +366,7 @@ impl<T> Field<T> {
     /// let x: Field<&str> = Present(None);
     /// assert_eq!(x.unwrap(), "air"); // fails
     /// ```
+    #[track_caller]
     pub fn unwrap(self) -> T {
         match self {
             Present(Some(t)) => t,
@@ -330,6 +396,7 @@ impl<T> Field<T> {
     /// let x: Field<&str> = Missing;
     /// assert_eq!(x.unwrap_present(), Some("air")); // fails
     /// ```
+    #[track_caller]
     pub fn unwrap_present(self) -> Option<T> {
         match self {
             Present(val) => val,
@@ -356,6 +423,7 @@ impl<T> Field<T> {
     /// let x: Field<&str> = Missing;
     /// assert_eq!(x.unwrap_present_ref(), &Some("air")); // fails
     /// ```
+    #[track_caller]
     pub fn unwrap_present_ref(&self) -> &Option<T> {
         match self {
             Present(ref val) => val,
@@ -382,6 +450,7 @@ impl<T> Field<T> {
     /// let mut x: Field<&str> = Missing;
     /// assert_eq!(x.unwrap_present_mut(), &mut Some("air")); // fails
     /// ```
+    #[track_caller]
     pub fn unwrap_present_mut(&mut self) -> &mut Option<T> {
         match self {
             Present(ref mut val) => val,
@@ -389,6 +458,93 @@ impl<T> Field<T> {
         }
     }
 
+    /// Returns the contained [`Some`] value, consuming the `self` value.
+    ///
+    /// Unlike [`unwrap`](Field::unwrap) this does not panic, but returns whether the value was
+    /// missing or null.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use optional_field::Field::{*, self};
+    /// # use optional_field::FieldError;
+    /// assert_eq!(Present(Some("air")).try_unwrap(), Ok("air"));
+    /// assert_eq!(Present::<&str>(None).try_unwrap(), Err(FieldError::Null));
+    /// assert_eq!(Missing::<&str>.try_unwrap(), Err(FieldError::Missing));
+    /// ```
+    pub fn try_unwrap(self) -> Result<T, FieldError> {
+        match self {
+            Present(Some(t)) => Ok(t),
+            Present(None) => Err(FieldError::Null),
+            Missing => Err(FieldError::Missing),
+        }
+    }
+
+    /// Returns the contained option, consuming the `self` value.
+    ///
+    /// Unlike [`unwrap_present`](Field::unwrap_present) this does not panic, but returns
+    /// [`FieldError::Missing`] if the value is missing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use optional_field::Field::{*, self};
+    /// # use optional_field::FieldError;
+    /// assert_eq!(Present(Some("air")).try_unwrap_present(), Ok(Some("air")));
+    /// assert_eq!(Present::<&str>(None).try_unwrap_present(), Ok(None));
+    /// assert_eq!(Missing::<&str>.try_unwrap_present(), Err(FieldError::Missing));
+    /// ```
+    pub fn try_unwrap_present(self) -> Result<Option<T>, FieldError> {
+        match self {
+            Present(val) => Ok(val),
+            Missing => Err(FieldError::Missing),
+        }
+    }
+
+    /// Returns a reference to the contained option.
+    ///
+    /// Unlike [`unwrap_present_ref`](Field::unwrap_present_ref) this does not panic, but returns
+    /// [`FieldError::Missing`] if the value is missing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use optional_field::Field::{*, self};
+    /// # use optional_field::FieldError;
+    /// assert_eq!(Present(Some("air")).try_unwrap_present_ref(), Ok(&Some("air")));
+    /// assert_eq!(Missing::<&str>.try_unwrap_present_ref(), Err(FieldError::Missing));
+    /// ```
+    pub fn try_unwrap_present_ref(&self) -> Result<&Option<T>, FieldError> {
+        match self {
+            Present(ref val) => Ok(val),
+            Missing => Err(FieldError::Missing),
+        }
+    }
+
+    /// Returns a mutable reference to the contained option.
+    ///
+    /// Unlike [`unwrap_present_mut`](Field::unwrap_present_mut) this does not panic, but returns
+    /// [`FieldError::Missing`] if the value is missing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use optional_field::Field::{*, self};
+    /// # use optional_field::FieldError;
+    /// let mut x = Present(Some("air"));
+    /// if let Ok(val) = x.try_unwrap_present_mut() {
+    ///     *val = None;
+    /// }
+    /// assert_eq!(x, Present(None));
+    /// assert_eq!(Missing::<&str>.try_unwrap_present_mut(), Err(FieldError::Missing));
+    /// ```
+    pub fn try_unwrap_present_mut(&mut self) -> Result<&mut Option<T>, FieldError> {
+        match self {
+            Present(ref mut val) => Ok(val),
+            Missing => Err(FieldError::Missing),
+        }
+    }
+
     /// Returns the contained [`Some`] value or a provided default.
     ///
     /// Arguments passed to `unwrap_or` are eagerly evaluated; if you are passing
@@ -484,6 +640,7 @@ impl<T> Field<T> {
     /// let x: Field<Option<&str>> = Missing;
     /// x.expect("fruits are healthy"); // panics with `fruits are healthy`
     /// ```
+    #[track_caller]
     pub fn expect(self, msg: &str) -> T {
         match self {
             Present(Some(val)) => val,
@@ -511,6 +668,7 @@ impl<T> Field<T> {
     /// let x: Field<Option<&str>> = Missing;
     /// x.expect("fruits are healthy"); // panics with `fruits are healthy`
     /// ```
+    #[track_caller]
     pub fn expect_present(self, msg: &str) -> Option<T> {
         match self {
             Present(val) => val,
@@ -567,7 +725,7 @@ impl<T> Field<T> {
     /// let x: Field<u32> = Present(None);
     /// let y: Field<&str> = Present(None);
     /// assert_eq!(x.and(y), Present(None));
-
+    ///
     /// let x: Field<u32> = Missing;
     /// let y: Field<&str> = Missing;
     /// assert_eq!(x.and(y), Missing);
@@ -601,7 +759,7 @@ impl<T> Field<T> {
     /// let x: Field<u32> = Present(None);
     /// let y: Field<&str> = Present(None);
     /// assert_eq!(x.and(y), Present(None));
-
+    ///
     /// let x: Field<u32> = Missing;
     /// let y: Field<&str> = Missing;
     /// assert_eq!(x.and(y), Missing);
@@ -701,6 +859,7 @@ impl<T> Field<T> {
     ///
     /// assert_eq!(x, Present(Some(7)));
     /// ```
+    #[track_caller]
     pub fn get_or_insert_present(&mut self, value: Option<T>) -> &mut Option<T> {
         if let Missing = *self {
             *self = Present(value);
@@ -965,10 +1124,14 @@ where
     where
         D: Deserializer<'de>,
     {
+        // Going through `Option` keeps the support serde has for options in buffered content,
+        // as used by flattened structs and internally tagged and untagged enums
         Option::<T>::deserialize(deserializer).map(Into::into)
     }
 }
 
+/// Serializes [`Missing`] as `null`, unless it is skipped with `skip_serializing_if`; see
+/// [`serde::missing`] for other policies.
 #[cfg(feature = "serde")]
 impl<T> Serialize for Field<T>
 where
@@ -0,0 +1,169 @@
+//! Update masks: the wire paths of the members a patch changes.
+//!
+//! Many APIs take an `update_mask` next to a patch, listing the paths of the members being
+//! changed. `#[derive(PresentPaths)]` lists the paths of every [`Field`] member that is not
+//! [`Missing`], and restricts a patch to the paths of a mask, using the serde name of each
+//! member. Members marked `#[field(nested)]` are recursed into and use dotted paths.
+//!
+//! # Examples
+//!
+//! ```
+//! # use optional_field::Field::{self, *};
+//! use optional_field::PresentPaths;
+//!
+//! #[derive(PresentPaths)]
+//! struct Address {
+//!     city: Field<String>,
+//!     zip: Field<String>,
+//! }
+//!
+//! #[derive(PresentPaths)]
+//! struct UserPatch {
+//!     name: Field<String>,
+//!     email: Field<String>,
+//!     #[field(nested)]
+//!     address: Field<Address>,
+//! }
+//!
+//! let mut patch = UserPatch {
+//!     name: Present(Some("bob".to_string())),
+//!     email: Present(None),
+//!     address: Present(Some(Address {
+//!         city: Present(Some("Oslo".to_string())),
+//!         zip: Missing,
+//!     })),
+//! };
+//!
+//! assert_eq!(patch.present_paths(), vec!["name", "email", "address.city"]);
+//!
+//! patch.restrict_to(&["email", "address.zip"]);
+//! assert_eq!(patch.present_paths(), vec!["email"]);
+//! ```
+
+use crate::Field::{self, *};
+
+/// A struct of [`Field`] members that can list and be restricted to the wire paths of its
+/// present members.
+///
+/// Usually derived, see the [module documentation](self).
+pub trait PresentPaths {
+    /// Appends the paths of the present members of `self`, found at `path`, to `paths`.
+    fn present_paths_at(&self, path: &str, paths: &mut Vec<String>);
+
+    /// Sets every member of `self`, found at `path`, that is not selected by `mask` to
+    /// [`Missing`].
+    fn restrict_to_at(&mut self, path: &str, mask: &[&str]);
+
+    /// Returns the dotted paths of the present members, in declaration order.
+    ///
+    /// A nested member is listed by its own path if it is null, and by the paths of its
+    /// present members otherwise.
+    fn present_paths(&self) -> Vec<String> {
+        let mut paths = Vec::new();
+        self.present_paths_at("", &mut paths);
+        paths
+    }
+
+    /// Sets every member not selected by `mask` to [`Missing`].
+    ///
+    /// A path selects its own member and everything nested in it.
+    fn restrict_to<S: AsRef<str>>(&mut self, mask: &[S])
+    where
+        Self: Sized,
+    {
+        let mask = mask.iter().map(AsRef::as_ref).collect::<Vec<_>>();
+        self.restrict_to_at("", &mask);
+    }
+}
+
+impl<T: PresentPaths> PresentPaths for Field<T> {
+    fn present_paths_at(&self, path: &str, paths: &mut Vec<String>) {
+        match self {
+            Missing => {}
+            Present(None) => paths.push(path.to_string()),
+            Present(Some(val)) => val.present_paths_at(path, paths),
+        }
+    }
+
+    fn restrict_to_at(&mut self, path: &str, mask: &[&str]) {
+        if selects(mask, path) {
+            return;
+        }
+        match self {
+            Present(Some(val)) if selects_within(mask, path) => val.restrict_to_at(path, mask),
+            _ => *self = Missing,
+        }
+    }
+}
+
+impl<T: PresentPaths> PresentPaths for Option<T> {
+    fn present_paths_at(&self, path: &str, paths: &mut Vec<String>) {
+        if let Some(val) = self {
+            val.present_paths_at(path, paths);
+        }
+    }
+
+    fn restrict_to_at(&mut self, path: &str, mask: &[&str]) {
+        if let Some(val) = self {
+            val.restrict_to_at(path, mask);
+        }
+    }
+}
+
+impl<T: PresentPaths + ?Sized> PresentPaths for Box<T> {
+    fn present_paths_at(&self, path: &str, paths: &mut Vec<String>) {
+        (**self).present_paths_at(path, paths);
+    }
+
+    fn restrict_to_at(&mut self, path: &str, mask: &[&str]) {
+        (**self).restrict_to_at(path, mask);
+    }
+}
+
+/// Appends `path` to `paths`, if `field` is present.
+pub fn push_present<T>(field: &Field<T>, path: String, paths: &mut Vec<String>) {
+    if field.is_present() {
+        paths.push(path);
+    }
+}
+
+/// Sets `field`, found at `path`, to [`Missing`] if it is not selected by `mask`.
+pub fn restrict<T>(field: &mut Field<T>, path: &str, mask: &[&str]) {
+    if !selects(mask, path) {
+        *field = Missing;
+    }
+}
+
+/// Is `path`, or one of its parents, listed in `mask`?
+///
+/// # Examples
+///
+/// ```
+/// # use optional_field::mask::selects;
+/// assert!(selects(&["address"], "address"));
+/// assert!(selects(&["address"], "address.city"));
+/// assert!(!selects(&["address"], "addressee"));
+/// assert!(!selects(&["address.city"], "address"));
+/// ```
+pub fn selects<S: AsRef<str>>(mask: &[S], path: &str) -> bool {
+    mask.iter().any(|masked| {
+        let masked = masked.as_ref();
+        path == masked || (path.starts_with(masked) && path[masked.len()..].starts_with('.'))
+    })
+}
+
+/// Is a path nested in `path` listed in `mask`?
+///
+/// # Examples
+///
+/// ```
+/// # use optional_field::mask::selects_within;
+/// assert!(selects_within(&["address.city"], "address"));
+/// assert!(!selects_within(&["address"], "address"));
+/// ```
+pub fn selects_within<S: AsRef<str>>(mask: &[S], path: &str) -> bool {
+    mask.iter().any(|masked| {
+        let masked = masked.as_ref();
+        masked.starts_with(path) && masked[path.len()..].starts_with('.')
+    })
+}
@@ -0,0 +1,64 @@
+use minicbor::data::Type;
+use minicbor::decode::{self, Decoder};
+use minicbor::encode::{self, Encoder, Write};
+use minicbor::{CborLen, Decode, Encode};
+
+use crate::Field::{self, *};
+
+/// [`Missing`] is encoded as the CBOR simple value `undefined` and `Present(None)` as `null`.
+///
+/// The serde based CBOR crates read `undefined` as `null` and cannot write it, which is why
+/// `Field` implements minicbor's own traits. Members of structs deriving `Encode` that are
+/// [`Missing`] are left out of maps, and a member with an absent key decodes as [`Missing`].
+///
+/// # Examples
+///
+/// ```
+/// # use optional_field::Field::{self, *};
+/// let fields = vec![Present(Some(3)), Missing, Present(None)];
+/// let bytes = minicbor::to_vec(&fields).unwrap();
+///
+/// assert_eq!(bytes, [0x83, 0x03, 0xf7, 0xf6]);
+/// assert_eq!(minicbor::decode::<Vec<Field<u8>>>(&bytes).unwrap(), fields);
+/// ```
+impl<C, T: Encode<C>> Encode<C> for Field<T> {
+    fn encode<W: Write>(
+        &self,
+        e: &mut Encoder<W>,
+        ctx: &mut C,
+    ) -> Result<(), encode::Error<W::Error>> {
+        match self {
+            Present(Some(val)) => val.encode(e, ctx),
+            Present(None) => e.null().map(|_| ()),
+            Missing => e.undefined().map(|_| ()),
+        }
+    }
+
+    fn is_nil(&self) -> bool {
+        self.is_missing()
+    }
+}
+
+impl<C, T: CborLen<C>> CborLen<C> for Field<T> {
+    fn cbor_len(&self, ctx: &mut C) -> usize {
+        match self {
+            Present(Some(val)) => val.cbor_len(ctx),
+            Present(None) | Missing => 1,
+        }
+    }
+}
+
+/// `undefined` is decoded as [`Missing`] and `null` as `Present(None)`.
+impl<'b, C, T: Decode<'b, C>> Decode<'b, C> for Field<T> {
+    fn decode(d: &mut Decoder<'b>, ctx: &mut C) -> Result<Self, decode::Error> {
+        match d.datatype()? {
+            Type::Undefined => d.undefined().map(|_| Missing),
+            Type::Null => d.null().map(|_| Present(None)),
+            _ => T::decode(d, ctx).map(Some).map(Present),
+        }
+    }
+
+    fn nil() -> Option<Self> {
+        Some(Missing)
+    }
+}
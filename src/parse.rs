@@ -0,0 +1,153 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::Field::{self, *};
+
+/// What an empty string is parsed as, see [`NullToken::empty_as`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EmptyAs {
+    /// An empty string is [`Missing`].
+    Missing,
+    /// An empty string is `Present(None)`.
+    Null,
+    /// An empty string is parsed like any other value, e.g. as an empty `String`.
+    Value,
+}
+
+/// How the text of a [`Field`] tells [`Missing`] and `Present(None)` from a value.
+///
+/// By default `"null"` is `Present(None)` and an empty string is [`Missing`].
+///
+/// # Examples
+///
+/// ```
+/// # use optional_field::Field::{*, self};
+/// # use optional_field::{EmptyAs, NullToken};
+/// let null = NullToken::new("NULL").empty_as(EmptyAs::Null);
+///
+/// assert_eq!(null.field("NULL"), Present(None));
+/// assert_eq!(null.field(""), Present(None));
+/// assert_eq!(null.field("null"), Present(Some("null")));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NullToken<'a> {
+    token: &'a str,
+    /// `None` until set with [`NullToken::empty_as`], so integrations can pick their own default
+    empty: Option<EmptyAs>,
+}
+
+impl<'a> NullToken<'a> {
+    /// Creates a policy where `token` is `Present(None)` and an empty string is [`Missing`].
+    pub const fn new(token: &'a str) -> Self {
+        NullToken { token, empty: None }
+    }
+
+    /// Sets what an empty string is parsed as.
+    ///
+    /// The null token takes precedence, so an empty token is always `Present(None)`.
+    pub const fn empty_as(self, empty: EmptyAs) -> Self {
+        NullToken {
+            empty: Some(empty),
+            ..self
+        }
+    }
+
+    /// Sets what an empty string is parsed as, unless [`empty_as`](NullToken::empty_as) was.
+    #[cfg(feature = "csv")]
+    pub(crate) const fn empty_as_or(self, empty: EmptyAs) -> Self {
+        match self.empty {
+            Some(_) => self,
+            None => self.empty_as(empty),
+        }
+    }
+
+    /// Returns the token that is parsed as `Present(None)`.
+    pub fn token(&self) -> &'a str {
+        self.token
+    }
+
+    /// Tells the state of `s` without parsing the value.
+    pub fn field<'s>(&self, s: &'s str) -> Field<&'s str> {
+        if s == self.token {
+            Present(None)
+        } else if s.is_empty() {
+            match self.empty.unwrap_or(EmptyAs::Missing) {
+                EmptyAs::Missing => Missing,
+                EmptyAs::Null => Present(None),
+                EmptyAs::Value => Present(Some(s)),
+            }
+        } else {
+            Present(Some(s))
+        }
+    }
+}
+
+impl Default for NullToken<'_> {
+    fn default() -> Self {
+        NullToken::new("null")
+    }
+}
+
+impl<T: FromStr> Field<T> {
+    /// Parses `s`, using `null` to tell [`Missing`] and `Present(None)` from a value, which is
+    /// parsed with `T::from_str`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use optional_field::Field::{*, self};
+    /// # use optional_field::{EmptyAs, NullToken};
+    /// let null = NullToken::new("~");
+    ///
+    /// assert_eq!(Field::<u8>::parse_with("2", null), Ok(Present(Some(2))));
+    /// assert_eq!(Field::<u8>::parse_with("~", null), Ok(Present(None)));
+    /// assert_eq!(Field::<u8>::parse_with("", null), Ok(Missing));
+    /// assert!(Field::<u8>::parse_with("", null.empty_as(EmptyAs::Value)).is_err());
+    /// ```
+    pub fn parse_with(s: &str, null: NullToken<'_>) -> Result<Field<T>, T::Err> {
+        match null.field(s) {
+            Present(Some(s)) => s.parse().map(Some).map(Present),
+            Present(None) => Ok(Present(None)),
+            Missing => Ok(Missing),
+        }
+    }
+}
+
+/// Parses with the default [`NullToken`], where `"null"` is `Present(None)` and an empty string
+/// is [`Missing`].
+///
+/// # Examples
+///
+/// ```
+/// # use optional_field::Field::{*, self};
+/// assert_eq!("2".parse(), Ok(Present(Some(2))));
+/// assert_eq!("null".parse::<Field<u8>>(), Ok(Present(None)));
+/// assert_eq!("".parse::<Field<u8>>(), Ok(Missing));
+/// ```
+impl<T: FromStr> FromStr for Field<T> {
+    type Err = T::Err;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Field::parse_with(s, NullToken::default())
+    }
+}
+
+/// Formats [`Missing`] as `<missing>`, `Present(None)` as `null` and a value as the value itself.
+///
+/// # Examples
+///
+/// ```
+/// # use optional_field::Field::{*, self};
+/// assert_eq!(Present(Some(2)).to_string(), "2");
+/// assert_eq!(Present::<u8>(None).to_string(), "null");
+/// assert_eq!(Missing::<u8>.to_string(), "<missing>");
+/// ```
+impl<T: fmt::Display> fmt::Display for Field<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Present(Some(val)) => val.fmt(f),
+            Present(None) => f.pad("null"),
+            Missing => f.pad("<missing>"),
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use prost_types::FieldMask;
+
+use crate::mask::{selects, PresentPaths};
+use crate::Field::{self, *};
+
+impl<T> Field<T> {
+    /// Converts a proto3 `optional` or wrapper value, where an unset value is [`Missing`].
+    ///
+    /// Use [`Field::from_masked`] if the message carries a field mask, to also get
+    /// `Present(None)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use optional_field::Field::{self, *};
+    /// assert_eq!(Field::from_proto(Some(2)), Present(Some(2)));
+    /// assert_eq!(Field::<u8>::from_proto(None), Missing);
+    /// ```
+    pub fn from_proto(value: Option<T>) -> Field<T> {
+        match value {
+            Some(value) => Present(Some(value)),
+            None => Missing,
+        }
+    }
+
+    /// Converts a proto3 `optional` or wrapper value, using `mask` to tell [`Missing`] from
+    /// `Present(None)`.
+    ///
+    /// `path` is present if it, or one of its parents, is listed in `mask`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use optional_field::Field::{self, *};
+    /// # use prost_types::FieldMask;
+    /// let mask = FieldMask {
+    ///     paths: vec!["name".to_string(), "address".to_string()],
+    /// };
+    ///
+    /// assert_eq!(Field::<String>::from_masked(None, &mask, "name"), Present(None));
+    /// assert_eq!(Field::<String>::from_masked(None, &mask, "email"), Missing);
+    /// assert_eq!(Field::from_masked(Some(1), &mask, "address.zip"), Present(Some(1)));
+    /// ```
+    pub fn from_masked(value: Option<T>, mask: &FieldMask, path: &str) -> Field<T> {
+        if selects(&mask.paths, path) {
+            Present(value)
+        } else {
+            Field::from_proto(value)
+        }
+    }
+
+    /// Converts into the proto3 `optional` or wrapper value, both [`Missing`] and
+    /// `Present(None)` are unset.
+    ///
+    /// Use a [`FieldMaskBuilder`] to keep the difference.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use optional_field::Field::*;
+    /// assert_eq!(Present(Some(2)).into_proto(), Some(2));
+    /// assert_eq!(Present::<u8>(None).into_proto(), None);
+    /// assert_eq!(Missing::<u8>.into_proto(), None);
+    /// ```
+    pub fn into_proto(self) -> Option<T> {
+        match self {
+            Present(value) => value,
+            Missing => None,
+        }
+    }
+}
+
+/// Builds the `google.protobuf.FieldMask` of an update from its [`Field`] members.
+///
+/// Every present member is listed, [`Missing`] members are not.
+///
+/// proto3 has presence tracking for `optional` scalars, wrapper types such as
+/// `google.protobuf.StringValue` and message fields, which prost all generates as `Option<T>`.
+/// There is no explicit null though, so a [`Field`] is encoded as the `Option<T>` value together
+/// with a `google.protobuf.FieldMask` listing the present paths:
+///
+/// | `Field<T>`          | value     | path in mask |
+/// |---------------------|-----------|--------------|
+/// | `Missing`           | `None`    | no           |
+/// | `Present(None)`     | `None`    | yes          |
+/// | `Present(Some(v))`  | `Some(v)` | yes          |
+///
+/// This is what the [AIP-134](https://google.aip.dev/134) update convention does: a path in
+/// the mask with an unset value clears the value.
+///
+/// # Examples
+///
+/// ```
+/// # use optional_field::Field::{self, *};
+/// # use optional_field::FieldMaskBuilder;
+/// struct UserPatch {
+///     name: Field<String>,
+///     email: Field<String>,
+///     age: Field<u32>,
+/// }
+///
+/// let patch = UserPatch {
+///     name: Present(Some("bob".to_string())),
+///     email: Present(None),
+///     age: Missing,
+/// };
+///
+/// let mask = FieldMaskBuilder::new()
+///     .field("name", &patch.name)
+///     .field("email", &patch.email)
+///     .field("age", &patch.age)
+///     .build();
+///
+/// assert_eq!(mask.paths, vec!["name", "email"]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FieldMaskBuilder {
+    paths: Vec<String>,
+}
+
+impl FieldMaskBuilder {
+    /// Creates a builder with no paths.
+    pub fn new() -> Self {
+        FieldMaskBuilder { paths: Vec::new() }
+    }
+
+    /// Adds `path`, if `field` is present.
+    pub fn field<T>(mut self, path: &str, field: &Field<T>) -> Self {
+        self.push(path, field);
+        self
+    }
+
+    /// Adds `path`, if `field` is present.
+    pub fn push<T>(&mut self, path: &str, field: &Field<T>) {
+        if field.is_present() {
+            self.push_path(path);
+        }
+    }
+
+    /// Adds the paths of all present members of `patch`, see [`PresentPaths`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use optional_field::Field::{self, *};
+    /// # use optional_field::{FieldMaskBuilder, PresentPaths};
+    /// #[derive(PresentPaths)]
+    /// struct UserPatch {
+    ///     name: Field<String>,
+    ///     age: Field<u32>,
+    /// }
+    ///
+    /// let patch = UserPatch {
+    ///     name: Present(None),
+    ///     age: Missing,
+    /// };
+    ///
+    /// let mask = FieldMaskBuilder::new().present(&patch).build();
+    /// assert_eq!(mask.paths, vec!["name"]);
+    /// ```
+    pub fn present<P: PresentPaths + ?Sized>(mut self, patch: &P) -> Self {
+        patch.present_paths_at("", &mut self.paths);
+        self
+    }
+
+    /// Adds `path` unconditionally.
+    pub fn push_path(&mut self, path: impl Into<String>) {
+        self.paths.push(path.into());
+    }
+
+    /// Returns the paths added so far.
+    pub fn paths(&self) -> &[String] {
+        &self.paths
+    }
+
+    /// Builds the field mask.
+    pub fn build(self) -> FieldMask {
+        FieldMask { paths: self.paths }
+    }
+}
+
+impl From<FieldMaskBuilder> for FieldMask {
+    fn from(builder: FieldMaskBuilder) -> FieldMask {
+        builder.build()
+    }
+}
@@ -0,0 +1,410 @@
+//! A MessagePack extension type for [`Missing`] values.
+//!
+//! MessagePack has no keys to leave out inside arrays, so this module writes [`Missing`] as an
+//! extension value without data, which `rmp_serde` reads back distinctly from `nil`. A
+//! `Vec<Field<T>>` therefore round-trips with [`seq`].
+//!
+//! [`serialize`] and [`deserialize`] are used with `#[serde(with = "optional_field::rmp")]` on a
+//! `Field<T>` member and [`Ext`] wraps a `Field<T>` in any other container. Extension values of
+//! other types are handed on to `T`.
+//!
+//! [`Missing`] is written with the extension type [`EXT_TYPE`] unless another one is given, as the
+//! parameter of [`Ext`] or to the `_as` functions, for applications that already use type 0:
+//!
+//! ```
+//! # use optional_field::Field::{self, *};
+//! # use serde::{Deserialize, Serialize};
+//! #[derive(Serialize, Deserialize)]
+//! struct Batch {
+//!     #[serde(
+//!         serialize_with = "optional_field::rmp::serialize_as::<42, _, _>",
+//!         deserialize_with = "optional_field::rmp::deserialize_as::<42, _, _>"
+//!     )]
+//!     score: Field<u8>,
+//! }
+//!
+//! let bytes = rmp_serde::to_vec(&Batch { score: Missing }).unwrap();
+//! assert_eq!(bytes, [0x91, 0xc7, 0x00, 42]);
+//! ```
+//!
+//! Values are read by their MessagePack type rather than by the type `T` asks for, so a
+//! `Field<Vec<u8>>` is read from an array rather than from binary data.
+//!
+//! # Examples
+//!
+//! ```
+//! use optional_field::Field::{self, *};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Batch {
+//!     #[serde(with = "optional_field::rmp::seq")]
+//!     scores: Vec<Field<u8>>,
+//! }
+//!
+//! let batch = Batch {
+//!     scores: vec![Present(Some(3)), Missing, Present(None)],
+//! };
+//! let bytes = rmp_serde::to_vec(&batch).unwrap();
+//!
+//! assert_eq!(rmp_serde::from_slice::<Batch>(&bytes).unwrap(), batch);
+//! ```
+
+use serde::de::value::{
+    BorrowedBytesDeserializer, BorrowedStrDeserializer, BytesDeserializer, EnumAccessDeserializer,
+    MapAccessDeserializer, SeqAccessDeserializer,
+};
+use serde::de::{
+    self, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, Visitor,
+};
+use serde::forward_to_deserialize_any;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::Field::{self, *};
+
+/// The extension type [`Missing`] is written with by default.
+pub const EXT_TYPE: i8 = 0;
+
+/// The name `rmp_serde` serializes extension values by.
+const EXT_STRUCT_NAME: &str = "_ExtStruct";
+
+/// Serializes [`Missing`] as an extension value of type [`EXT_TYPE`].
+pub fn serialize<T, S>(field: &Field<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Serialize,
+    S: Serializer,
+{
+    serialize_as::<EXT_TYPE, T, S>(field, serializer)
+}
+
+/// Deserializes an extension value of type [`EXT_TYPE`] as [`Missing`].
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Field<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    deserialize_as::<EXT_TYPE, T, D>(deserializer)
+}
+
+/// Serializes [`Missing`] as an extension value of type `TYPE`.
+pub fn serialize_as<const TYPE: i8, T, S>(
+    field: &Field<T>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    T: Serialize,
+    S: Serializer,
+{
+    match field {
+        Present(Some(val)) => val.serialize(serializer),
+        Present(None) => serializer.serialize_none(),
+        Missing => serializer.serialize_newtype_struct(EXT_STRUCT_NAME, &(TYPE, ExtData(&[]))),
+    }
+}
+
+/// Deserializes an extension value of type `TYPE` as [`Missing`].
+pub fn deserialize_as<'de, const TYPE: i8, T, D>(deserializer: D) -> Result<Field<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(FieldVisitor::<T, TYPE>(PhantomData))
+}
+
+/// [`serialize`] and [`deserialize`] for every `Field<T>` of a `Vec<Field<T>>`.
+///
+/// To be used with `#[serde(with = "optional_field::rmp::seq")]`.
+pub mod seq {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{Ext, ExtRef, EXT_TYPE};
+    use crate::Field;
+
+    /// Serializes every [`Missing`](crate::Field::Missing) element as an extension value of type
+    /// [`EXT_TYPE`].
+    pub fn serialize<T, S>(fields: &[Field<T>], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        serialize_as::<EXT_TYPE, T, S>(fields, serializer)
+    }
+
+    /// Deserializes every extension value of type [`EXT_TYPE`] as
+    /// [`Missing`](crate::Field::Missing).
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Vec<Field<T>>, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        deserialize_as::<EXT_TYPE, T, D>(deserializer)
+    }
+
+    /// Serializes every [`Missing`](crate::Field::Missing) element as an extension value of type
+    /// `TYPE`.
+    pub fn serialize_as<const TYPE: i8, T, S>(
+        fields: &[Field<T>],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        serializer.collect_seq(fields.iter().map(ExtRef::<T, TYPE>))
+    }
+
+    /// Deserializes every extension value of type `TYPE` as [`Missing`](crate::Field::Missing).
+    pub fn deserialize_as<'de, const TYPE: i8, T, D>(
+        deserializer: D,
+    ) -> Result<Vec<Field<T>>, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        let fields = Vec::<Ext<T, TYPE>>::deserialize(deserializer)?;
+        Ok(fields.into_iter().map(|field| field.0).collect())
+    }
+}
+
+/// A `Field<T>` writing [`Missing`] as an extension value of type `TYPE`, for use in maps and
+/// other containers.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Ext<T, const TYPE: i8 = EXT_TYPE>(pub Field<T>);
+
+impl<T, const TYPE: i8> From<Field<T>> for Ext<T, TYPE> {
+    fn from(field: Field<T>) -> Self {
+        Ext(field)
+    }
+}
+
+impl<T, const TYPE: i8> From<Ext<T, TYPE>> for Field<T> {
+    fn from(ext: Ext<T, TYPE>) -> Self {
+        ext.0
+    }
+}
+
+impl<T: Serialize, const TYPE: i8> Serialize for Ext<T, TYPE> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_as::<TYPE, T, S>(&self.0, serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>, const TYPE: i8> Deserialize<'de> for Ext<T, TYPE> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_as::<TYPE, T, D>(deserializer).map(Ext)
+    }
+}
+
+struct ExtRef<'a, T, const TYPE: i8>(&'a Field<T>);
+
+impl<T: Serialize, const TYPE: i8> Serialize for ExtRef<'_, T, TYPE> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_as::<TYPE, T, S>(self.0, serializer)
+    }
+}
+
+/// The data of an extension value, which `rmp_serde` reads and writes as bytes.
+struct ExtData<'a>(&'a [u8]);
+
+impl Serialize for ExtData<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+struct ExtDataBuf(Vec<u8>);
+
+impl<'de> Deserialize<'de> for ExtDataBuf {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct BytesVisitor;
+
+        impl<'de> Visitor<'de> for BytesVisitor {
+            type Value = ExtDataBuf;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "extension data")
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<ExtDataBuf, E> {
+                Ok(ExtDataBuf(v.to_vec()))
+            }
+        }
+
+        deserializer.deserialize_bytes(BytesVisitor)
+    }
+}
+
+fn present<'de, T, D>(deserializer: D) -> Result<Field<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some).map(Present)
+}
+
+/// Reads a `Field<T>`, where an extension value of type `TYPE` is [`Missing`].
+///
+/// The value is read with `deserialize_any`, so `T` is handed the type stored in the message
+/// rather than the one it asks for. Extension values, which `rmp_serde` visits as newtype
+/// structs, are handed on to `T` unless they are of type `TYPE`.
+struct FieldVisitor<T, const TYPE: i8>(PhantomData<T>);
+
+macro_rules! visit_into_deserializer {
+    ($($method:ident($ty:ty),)*) => {
+        $(
+            fn $method<E: de::Error>(self, v: $ty) -> Result<Field<T>, E> {
+                present(v.into_deserializer())
+            }
+        )*
+    };
+}
+
+impl<'de, T: Deserialize<'de>, const TYPE: i8> Visitor<'de> for FieldVisitor<T, TYPE> {
+    type Value = Field<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a value, null or a missing extension value")
+    }
+
+    visit_into_deserializer! {
+        visit_bool(bool),
+        visit_i8(i8),
+        visit_i16(i16),
+        visit_i32(i32),
+        visit_i64(i64),
+        visit_i128(i128),
+        visit_u8(u8),
+        visit_u16(u16),
+        visit_u32(u32),
+        visit_u64(u64),
+        visit_u128(u128),
+        visit_f32(f32),
+        visit_f64(f64),
+        visit_char(char),
+        visit_str(&str),
+        visit_string(String),
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Field<T>, E> {
+        present(BorrowedStrDeserializer::new(v))
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Field<T>, E> {
+        present(BytesDeserializer::new(v))
+    }
+
+    fn visit_borrowed_bytes<E: de::Error>(self, v: &'de [u8]) -> Result<Field<T>, E> {
+        present(BorrowedBytesDeserializer::new(v))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Field<T>, E> {
+        present(BytesDeserializer::new(&v))
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Field<T>, E> {
+        Ok(Present(None))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Field<T>, E> {
+        Ok(Present(None))
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Field<T>, D::Error> {
+        present(deserializer)
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Field<T>, D::Error> {
+        let (ext_type, ExtDataBuf(data)) = <(i8, ExtDataBuf)>::deserialize(deserializer)?;
+        if ext_type == TYPE {
+            Ok(Missing)
+        } else {
+            present(Reread {
+                ext_type,
+                data,
+                error: PhantomData,
+            })
+        }
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Field<T>, A::Error> {
+        present(SeqAccessDeserializer::new(seq))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Field<T>, A::Error> {
+        present(MapAccessDeserializer::new(map))
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Field<T>, A::Error> {
+        present(EnumAccessDeserializer::new(data))
+    }
+}
+
+/// Hands an extension value that has already been read on to the deserializer of `T`.
+struct Reread<E> {
+    ext_type: i8,
+    data: Vec<u8>,
+    error: PhantomData<E>,
+}
+
+impl<'de, E: de::Error> Deserializer<'de> for Reread<E> {
+    type Error = E;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+        visitor.visit_newtype_struct(RereadSeq { ext: self, read: 0 })
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+/// The extension type and data, as `rmp_serde` hands them to a newtype struct.
+struct RereadSeq<E> {
+    ext: Reread<E>,
+    read: usize,
+}
+
+impl<'de, E: de::Error> Deserializer<'de> for RereadSeq<E> {
+    type Error = E;
+
+    fn deserialize_any<V: Visitor<'de>>(mut self, visitor: V) -> Result<V::Value, E> {
+        visitor.visit_seq(&mut self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+impl<'de, E: de::Error> SeqAccess<'de> for RereadSeq<E> {
+    type Error = E;
+
+    fn next_element_seed<S: DeserializeSeed<'de>>(
+        &mut self,
+        seed: S,
+    ) -> Result<Option<S::Value>, E> {
+        self.read += 1;
+        match self.read {
+            1 => seed
+                .deserialize(self.ext.ext_type.into_deserializer())
+                .map(Some),
+            2 => seed
+                .deserialize(BytesDeserializer::new(&self.ext.data))
+                .map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(2 - self.read.min(2))
+    }
+}
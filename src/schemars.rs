@@ -0,0 +1,27 @@
+use std::borrow::Cow;
+
+use schemars::{JsonSchema, Schema, SchemaGenerator};
+
+use crate::Field;
+
+/// A `Field` is described like an `Option`: the value's schema, also allowing `null`.
+///
+/// Whether the member may be left out is decided by the containing struct. Members of structs
+/// using `serde_optional_fields` get `#[serde(default)]`, so they are not listed as `required`.
+impl<T: JsonSchema> JsonSchema for Field<T> {
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        <Option<T>>::schema_name()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        format!("optional_field::Field<{}>", T::schema_id()).into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        <Option<T>>::json_schema(generator)
+    }
+}
@@ -0,0 +1,473 @@
+//! serde adapters for [`Field`](crate::Field) members that need an encoding of their own.
+
+/// Encodes a [`Field`] as a sequence of at most one element, for members of tuple structs and
+/// tuple variants.
+///
+/// Skipping a member of a tuple shifts the position of every member after it, so
+/// `#[serde_optional_fields(positional)]` uses this module for the `Field` members of tuple
+/// structs and variants with more than one member: [`Missing`] is written as
+/// `[]`, `Present(None)` as `[null]` and `Present(Some(value))` as `[value]`. Newtype structs and
+/// variants have no positions to shift and are left alone.
+///
+/// Members that already have a `with`, `serialize_with`, `deserialize_with` or
+/// `skip_serializing_if` attribute are left alone as well. Without `positional`, any other `Field`
+/// member of such a tuple is a compile error, rather than being skipped when [`Missing`]:
+///
+/// ```compile_fail
+/// # use optional_field::Field;
+/// # use optional_field::serde_optional_fields;
+/// # use serde::Serialize;
+/// #[serde_optional_fields]
+/// #[derive(Serialize)]
+/// struct Point(Field<u8>, Field<u8>);
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// use optional_field::Field::{self, *};
+/// use optional_field::serde_optional_fields;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[serde_optional_fields(positional)]
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Point(Field<u8>, Field<u8>, Field<u8>);
+///
+/// let point = Point(Missing, Present(None), Present(Some(3)));
+/// let json = serde_json::to_string(&point).unwrap();
+///
+/// assert_eq!(json, "[[],[null],[3]]");
+/// assert_eq!(serde_json::from_str::<Point>(&json).unwrap(), point);
+/// ```
+///
+/// [`Missing`]: crate::Field::Missing
+/// [`Field`]: crate::Field
+pub mod positional {
+    use serde::de::{self, SeqAccess, Visitor};
+    use serde::ser::SerializeSeq;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::fmt;
+    use std::marker::PhantomData;
+
+    use crate::Field::{self, *};
+
+    /// Serializes [`Missing`] as an empty sequence and `Present(opt)` as `[opt]`.
+    pub fn serialize<T, S>(field: &Field<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        match field {
+            Present(opt) => {
+                let mut seq = serializer.serialize_seq(Some(1))?;
+                seq.serialize_element(opt)?;
+                seq.end()
+            }
+            Missing => serializer.serialize_seq(Some(0))?.end(),
+        }
+    }
+
+    /// Deserializes an empty sequence as [`Missing`] and `[opt]` as `Present(opt)`.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Field<T>, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(PositionalVisitor(PhantomData))
+    }
+
+    struct PositionalVisitor<T>(PhantomData<T>);
+
+    impl<'de, T: Deserialize<'de>> Visitor<'de> for PositionalVisitor<T> {
+        type Value = Field<T>;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "a sequence of at most one element")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Field<T>, A::Error> {
+            let field = match seq.next_element::<Option<T>>()? {
+                Some(opt) => Present(opt),
+                None => return Ok(Missing),
+            };
+            if seq.next_element::<de::IgnoredAny>()?.is_some() {
+                return Err(de::Error::invalid_length(2, &self));
+            }
+            Ok(field)
+        }
+    }
+}
+
+/// Rejects `null` for a [`Field`] member, so that it is either [`Missing`] or has a value.
+///
+/// A plain `Field<T>` accepts `null` and everything `T` accepts, and members of a
+/// [`serde_optional_fields`](crate::serde_optional_fields) struct can be left out. In an
+/// `#[serde(untagged)]` enum the first variant made of such members therefore matches almost any
+/// payload. Marking the members a variant does not allow to be `null` with
+/// `#[serde(with = "optional_field::serde::strict")]` makes payloads with `null` there fall
+/// through to the next variant. Adding `#[serde(deny_unknown_fields)]` to the enum further keeps
+/// a variant from matching payloads with keys of another variant.
+///
+/// Serializing is the same as for a plain `Field<T>`.
+///
+/// # Examples
+///
+/// ```
+/// use optional_field::Field::{self, *};
+/// use optional_field::serde_optional_fields;
+/// use serde::Deserialize;
+///
+/// #[serde_optional_fields]
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// #[serde(untagged, deny_unknown_fields)]
+/// enum Webhook {
+///     Paid {
+///         id: Field<u64>,
+///         #[serde(with = "optional_field::serde::strict")]
+///         amount: Field<u64>,
+///     },
+///     Voided {
+///         id: Field<u64>,
+///         amount: Field<u64>,
+///     },
+/// }
+///
+/// let webhook: Webhook = serde_json::from_str(r#"{"id": 1, "amount": null}"#).unwrap();
+///
+/// assert_eq!(
+///     webhook,
+///     Webhook::Voided {
+///         id: Present(Some(1)),
+///         amount: Present(None)
+///     }
+/// );
+/// ```
+///
+/// [`Missing`]: crate::Field::Missing
+/// [`Field`]: crate::Field
+pub mod strict {
+    use serde::de::{self, Unexpected};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::Field::{self, *};
+
+    /// Serializes like `Field<T>` itself.
+    pub fn serialize<T, S>(field: &Field<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        field.serialize(serializer)
+    }
+
+    /// Deserializes a value as `Present(Some(value))` and fails on `null`.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Field<T>, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        match Option::<T>::deserialize(deserializer)? {
+            Some(val) => Ok(Present(Some(val))),
+            None => Err(de::Error::invalid_type(
+                Unexpected::Other("null"),
+                &"a value other than null",
+            )),
+        }
+    }
+}
+
+/// Chooses what a [`Missing`] value is serialized as when it is not skipped.
+///
+/// `Field<T>` itself serializes [`Missing`] as `null`, which silently turns it into
+/// `Present(None)` wherever it cannot be skipped, such as in a `Vec`, a map value or a struct
+/// without [`serde_optional_fields`](crate::serde_optional_fields). A [`SerializeMissing`] policy
+/// chooses otherwise:
+///
+/// * [`Error`] fails to serialize [`Missing`]
+/// * [`Null`] writes `null`, like `Field<T>` itself
+/// * [`Unit`] writes a unit, which formats such as RON tell apart from `null`
+///
+/// Each policy has a module to be used with `#[serde(with = "...")]`, e.g.
+/// `optional_field::serde::missing::error`, with a `seq` module for a `Vec<Field<T>>`.
+/// [`MissingAs`] applies a policy to a `Field` anywhere else, and is the only way to use a policy
+/// of your own.
+///
+/// Members skipped with `skip_serializing_if`, as added by
+/// [`serde_optional_fields`](crate::serde_optional_fields), are never passed to the policy.
+///
+/// # Examples
+///
+/// ```
+/// use optional_field::Field::{self, *};
+/// use optional_field::serde_optional_fields;
+/// use serde::Serialize;
+///
+/// #[serde_optional_fields]
+/// #[derive(Serialize)]
+/// struct Patch {
+///     #[serde(with = "optional_field::serde::missing::error")]
+///     name: Field<String>,
+///     #[serde(with = "optional_field::serde::missing::error::seq")]
+///     tags: Vec<Field<String>>,
+/// }
+///
+/// let patch = Patch {
+///     name: Missing,
+///     tags: vec![Present(None)],
+/// };
+/// assert_eq!(serde_json::to_string(&patch).unwrap(), r#"{"tags":[null]}"#);
+///
+/// let patch = Patch {
+///     name: Missing,
+///     tags: vec![Missing],
+/// };
+/// assert!(serde_json::to_string(&patch).is_err());
+/// ```
+///
+/// [`Missing`]: crate::Field::Missing
+/// [`SerializeMissing`]: missing::SerializeMissing
+/// [`Error`]: missing::Error
+/// [`Null`]: missing::Null
+/// [`Unit`]: missing::Unit
+/// [`MissingAs`]: missing::MissingAs
+pub mod missing {
+    use serde::ser::Error as _;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::fmt;
+    use std::marker::PhantomData;
+    use std::ops::{Deref, DerefMut};
+
+    use crate::Field::{self, *};
+
+    /// What a [`Missing`] value is serialized as.
+    ///
+    /// # Examples
+    ///
+    /// A policy writing a sentinel:
+    ///
+    /// ```
+    /// use optional_field::serde::missing::{MissingAs, SerializeMissing};
+    /// use optional_field::Field::*;
+    /// use serde::Serializer;
+    ///
+    /// enum Undefined {}
+    ///
+    /// impl SerializeMissing for Undefined {
+    ///     fn serialize_missing<S: Serializer>(serializer: S) -> Result<S::Ok, S::Error> {
+    ///         serializer.serialize_str("$undefined")
+    ///     }
+    /// }
+    ///
+    /// let fields: Vec<MissingAs<Undefined, u8>> = vec![Missing.into(), Present(None).into()];
+    ///
+    /// assert_eq!(serde_json::to_string(&fields).unwrap(), r#"["$undefined",null]"#);
+    /// ```
+    pub trait SerializeMissing {
+        /// Serializes [`Missing`].
+        fn serialize_missing<S: Serializer>(serializer: S) -> Result<S::Ok, S::Error>;
+    }
+
+    /// Fails to serialize [`Missing`].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Error {}
+
+    /// Serializes [`Missing`] as `null`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Null {}
+
+    /// Serializes [`Missing`] as a unit.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Unit {}
+
+    impl SerializeMissing for Error {
+        fn serialize_missing<S: Serializer>(_serializer: S) -> Result<S::Ok, S::Error> {
+            Err(S::Error::custom(
+                "`Missing` must be skipped, as it cannot be serialized here",
+            ))
+        }
+    }
+
+    impl SerializeMissing for Null {
+        fn serialize_missing<S: Serializer>(serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_none()
+        }
+    }
+
+    impl SerializeMissing for Unit {
+        fn serialize_missing<S: Serializer>(serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_unit()
+        }
+    }
+
+    /// Serializes `field`, using the policy `P` for [`Missing`].
+    pub fn serialize_with<P, T, S>(field: &Field<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        P: SerializeMissing,
+        T: Serialize,
+        S: Serializer,
+    {
+        match field {
+            Present(opt) => opt.serialize(serializer),
+            Missing => P::serialize_missing(serializer),
+        }
+    }
+
+    /// A `Field<T>` serialized with the policy `P`, for use in maps and other containers.
+    ///
+    /// Deserializes like `Field<T>` itself.
+    #[derive(Clone, PartialEq, Eq)]
+    pub struct MissingAs<P, T> {
+        field: Field<T>,
+        policy: PhantomData<P>,
+    }
+
+    impl<P, T> MissingAs<P, T> {
+        /// Wraps `field`.
+        pub fn new(field: Field<T>) -> Self {
+            MissingAs {
+                field,
+                policy: PhantomData,
+            }
+        }
+
+        /// Returns the wrapped field.
+        pub fn into_inner(self) -> Field<T> {
+            self.field
+        }
+    }
+
+    impl<P, T: fmt::Debug> fmt::Debug for MissingAs<P, T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_tuple("MissingAs").field(&self.field).finish()
+        }
+    }
+
+    impl<P, T> Default for MissingAs<P, T> {
+        fn default() -> Self {
+            MissingAs::new(Missing)
+        }
+    }
+
+    impl<P, T> Deref for MissingAs<P, T> {
+        type Target = Field<T>;
+
+        fn deref(&self) -> &Field<T> {
+            &self.field
+        }
+    }
+
+    impl<P, T> DerefMut for MissingAs<P, T> {
+        fn deref_mut(&mut self) -> &mut Field<T> {
+            &mut self.field
+        }
+    }
+
+    impl<P, T> From<Field<T>> for MissingAs<P, T> {
+        fn from(field: Field<T>) -> Self {
+            MissingAs::new(field)
+        }
+    }
+
+    impl<P, T> From<MissingAs<P, T>> for Field<T> {
+        fn from(wrapped: MissingAs<P, T>) -> Self {
+            wrapped.field
+        }
+    }
+
+    impl<P: SerializeMissing, T: Serialize> Serialize for MissingAs<P, T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serialize_with::<P, T, S>(&self.field, serializer)
+        }
+    }
+
+    impl<'de, P, T: Deserialize<'de>> Deserialize<'de> for MissingAs<P, T> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            Field::deserialize(deserializer).map(MissingAs::new)
+        }
+    }
+
+    macro_rules! policy_module {
+        ($(#[$doc:meta] $module:ident => $policy:ident,)*) => {
+            $(
+                #[$doc]
+                ///
+                /// Deserializes like `Field<T>` itself.
+                pub mod $module {
+                    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+                    use super::$policy;
+                    use crate::Field;
+
+                    /// Serializes `field`, see the module documentation.
+                    pub fn serialize<T, S>(field: &Field<T>, serializer: S) -> Result<S::Ok, S::Error>
+                    where
+                        T: Serialize,
+                        S: Serializer,
+                    {
+                        super::serialize_with::<$policy, T, S>(field, serializer)
+                    }
+
+                    /// Deserializes like `Field<T>` itself.
+                    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Field<T>, D::Error>
+                    where
+                        T: Deserialize<'de>,
+                        D: Deserializer<'de>,
+                    {
+                        Field::deserialize(deserializer)
+                    }
+
+                    /// The same for every `Field<T>` of a `Vec<Field<T>>`.
+                    pub mod seq {
+                        use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+                        use super::super::$policy;
+                        use crate::Field;
+
+                        /// Serializes every element, see the module documentation.
+                        pub fn serialize<T, S>(
+                            fields: &[Field<T>],
+                            serializer: S,
+                        ) -> Result<S::Ok, S::Error>
+                        where
+                            T: Serialize,
+                            S: Serializer,
+                        {
+                            serializer.collect_seq(fields.iter().map(|field| FieldRef(field)))
+                        }
+
+                        /// Deserializes like `Vec<Field<T>>` itself.
+                        pub fn deserialize<'de, T, D>(
+                            deserializer: D,
+                        ) -> Result<Vec<Field<T>>, D::Error>
+                        where
+                            T: Deserialize<'de>,
+                            D: Deserializer<'de>,
+                        {
+                            Vec::deserialize(deserializer)
+                        }
+
+                        struct FieldRef<'a, T>(&'a Field<T>);
+
+                        impl<T: Serialize> Serialize for FieldRef<'_, T> {
+                            fn serialize<S: Serializer>(
+                                &self,
+                                serializer: S,
+                            ) -> Result<S::Ok, S::Error> {
+                                super::super::serialize_with::<$policy, T, S>(self.0, serializer)
+                            }
+                        }
+                    }
+                }
+            )*
+        };
+    }
+
+    policy_module! {
+        /// Fails to serialize [`Missing`].
+        error => Error,
+        /// Serializes [`Missing`] as `null`.
+        null => Null,
+        /// Serializes [`Missing`] as a unit.
+        unit => Unit,
+    }
+}
@@ -0,0 +1,18 @@
+use specta::datatype::reference::Reference;
+use specta::{DataType, Generics, Type, TypeCollection};
+
+use crate::Field;
+
+/// A `Field<T>` is typed like an `Option<T>`, as a nullable `T`.
+///
+/// Members of structs using `serde_optional_fields` have `#[serde(default)]`, so specta marks
+/// them optional.
+impl<T: Type> Type for Field<T> {
+    fn inline(type_map: &mut TypeCollection, generics: Generics) -> DataType {
+        <Option<T>>::inline(type_map, generics)
+    }
+
+    fn reference(type_map: &mut TypeCollection, generics: &[DataType]) -> Reference {
+        <Option<T>>::reference(type_map, generics)
+    }
+}
@@ -0,0 +1,426 @@
+//! `UPDATE ... SET` statements from patches of [`Field`] members.
+//!
+//! Each member of a patch becomes an [`Assignment`] of its column:
+//!
+//! * [`Missing`] members are left out
+//! * `Present(None)` sets the column to `NULL`
+//! * `Present(Some(v))` sets the column to a bound parameter
+//!
+//! `#[derive(SqlPatch)]` collects the assignments of all `Field` members, using the member name
+//! as column unless it is overridden with `#[field(column = "...")]`. An [`UpdateBuilder`] turns
+//! them into a statement and its bind values, which can be passed to any driver.
+//! [`SqlPatch::assignments`] can also be used to feed another query builder.
+//!
+//! # Examples
+//!
+//! ```
+//! # use optional_field::Field::{self, *};
+//! use optional_field::sql::{UpdateBuilder, Value};
+//! use optional_field::SqlPatch;
+//!
+//! #[derive(SqlPatch)]
+//! struct UserPatch {
+//!     name: Field<String>,
+//!     #[field(column = "email_address")]
+//!     email: Field<String>,
+//!     age: Field<u32>,
+//! }
+//!
+//! let patch = UserPatch {
+//!     name: Present(Some("bob".to_string())),
+//!     email: Present(None),
+//!     age: Missing,
+//! };
+//!
+//! let (sql, values) = UpdateBuilder::new("users")
+//!     .set_patch(&patch)
+//!     .filter("id", 7)
+//!     .build()
+//!     .unwrap();
+//!
+//! assert_eq!(sql, "UPDATE users SET name = ?, email_address = NULL WHERE id = ?");
+//! assert_eq!(values, vec![Value::Text("bob".to_string()), Value::Int(7)]);
+//! ```
+//!
+//! Table and column names are inserted into the statement as they are, so they must not come
+//! from user input.
+
+use std::borrow::Cow;
+use std::fmt::Write;
+
+use crate::Field::{self, *};
+
+/// A value bound to a statement parameter.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+/// Conversion of a member value into a [`Value`].
+pub trait ToValue {
+    fn to_value(&self) -> Value;
+}
+
+impl ToValue for Value {
+    fn to_value(&self) -> Value {
+        self.clone()
+    }
+}
+
+impl<T: ToValue + ?Sized> ToValue for &T {
+    fn to_value(&self) -> Value {
+        (**self).to_value()
+    }
+}
+
+impl<T: ToValue> ToValue for Option<T> {
+    fn to_value(&self) -> Value {
+        match self {
+            Some(val) => val.to_value(),
+            None => Value::Null,
+        }
+    }
+}
+
+impl ToValue for bool {
+    fn to_value(&self) -> Value {
+        Value::Bool(*self)
+    }
+}
+
+macro_rules! impl_to_value {
+    ($variant:ident($as:ty): $($ty:ty),*) => {
+        $(
+            impl ToValue for $ty {
+                fn to_value(&self) -> Value {
+                    Value::$variant(<$as>::from(*self))
+                }
+            }
+        )*
+    };
+}
+
+impl_to_value!(Int(i64): i8, i16, i32, i64, u8, u16, u32);
+impl_to_value!(Float(f64): f32, f64);
+
+impl ToValue for str {
+    fn to_value(&self) -> Value {
+        Value::Text(self.to_string())
+    }
+}
+
+impl ToValue for String {
+    fn to_value(&self) -> Value {
+        Value::Text(self.clone())
+    }
+}
+
+impl ToValue for [u8] {
+    fn to_value(&self) -> Value {
+        Value::Bytes(self.to_vec())
+    }
+}
+
+impl ToValue for Vec<u8> {
+    fn to_value(&self) -> Value {
+        Value::Bytes(self.clone())
+    }
+}
+
+/// A `column = value` pair of a `SET` clause.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assignment {
+    pub column: Cow<'static, str>,
+    pub value: Value,
+}
+
+impl Assignment {
+    /// Returns the assignment of `field` to `column`, or `None` if `field` is [`Missing`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use optional_field::Field::*;
+    /// use optional_field::sql::{Assignment, Value};
+    ///
+    /// let set = Assignment::from_field("age", &Present::<u8>(None)).unwrap();
+    /// assert_eq!(set.value, Value::Null);
+    /// assert_eq!(Assignment::from_field("age", &Missing::<u8>), None);
+    /// ```
+    pub fn from_field<T: ToValue>(
+        column: impl Into<Cow<'static, str>>,
+        field: &Field<T>,
+    ) -> Option<Assignment> {
+        match field {
+            Missing => None,
+            Present(val) => Some(Assignment {
+                column: column.into(),
+                value: val.to_value(),
+            }),
+        }
+    }
+}
+
+/// A patch whose present members can be assigned to columns.
+///
+/// Usually derived, see the [module documentation](self).
+pub trait SqlPatch {
+    /// Appends the assignments of all present members to `assignments`.
+    fn assignments_into(&self, assignments: &mut Vec<Assignment>);
+
+    /// Returns the assignments of all present members, in declaration order.
+    fn assignments(&self) -> Vec<Assignment> {
+        let mut assignments = Vec::new();
+        self.assignments_into(&mut assignments);
+        assignments
+    }
+}
+
+/// The parameter syntax of a database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placeholder {
+    /// `?`, used by SQLite and MySQL
+    Question,
+    /// `$1`, `$2`, ..., used by PostgreSQL
+    Dollar,
+}
+
+/// Builds an `UPDATE table SET ... WHERE ...` statement with bound parameters.
+///
+/// `Present(None)` members are written as `NULL` literals, all other values are bound.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdateBuilder {
+    table: String,
+    assignments: Vec<Assignment>,
+    filters: Vec<(String, Value)>,
+    placeholder: Placeholder,
+}
+
+impl UpdateBuilder {
+    /// Creates a builder updating `table`, using `?` placeholders.
+    pub fn new(table: impl Into<String>) -> Self {
+        UpdateBuilder {
+            table: table.into(),
+            assignments: Vec::new(),
+            filters: Vec::new(),
+            placeholder: Placeholder::Question,
+        }
+    }
+
+    /// Sets the parameter syntax.
+    pub fn placeholder(mut self, placeholder: Placeholder) -> Self {
+        self.placeholder = placeholder;
+        self
+    }
+
+    /// Assigns `field` to `column`, unless it is [`Missing`].
+    pub fn set<T: ToValue>(
+        mut self,
+        column: impl Into<Cow<'static, str>>,
+        field: &Field<T>,
+    ) -> Self {
+        self.assignments
+            .extend(Assignment::from_field(column, field));
+        self
+    }
+
+    /// Assigns all present members of `patch`.
+    pub fn set_patch<P: SqlPatch + ?Sized>(mut self, patch: &P) -> Self {
+        patch.assignments_into(&mut self.assignments);
+        self
+    }
+
+    /// Adds a `column = value` condition, or `column IS NULL` for a null `value`.
+    ///
+    /// All conditions must hold.
+    pub fn filter(mut self, column: impl Into<String>, value: impl ToValue) -> Self {
+        self.filters.push((column.into(), value.to_value()));
+        self
+    }
+
+    /// Returns `true` if nothing would be updated.
+    pub fn is_empty(&self) -> bool {
+        self.assignments.is_empty()
+    }
+
+    /// Returns the assignments added so far.
+    pub fn assignments(&self) -> &[Assignment] {
+        &self.assignments
+    }
+
+    /// Builds the statement and its bind values.
+    ///
+    /// Returns `None` if there is nothing to update, as `UPDATE t SET WHERE ...` is not valid SQL.
+    pub fn build(self) -> Option<(String, Vec<Value>)> {
+        if self.is_empty() {
+            return None;
+        }
+        let mut sql = format!("UPDATE {} SET ", self.table);
+        let mut values = Vec::new();
+        for (i, assignment) in self.assignments.into_iter().enumerate() {
+            if i > 0 {
+                sql.push_str(", ");
+            }
+            write!(sql, "{} = ", assignment.column).unwrap();
+            push_value(&mut sql, &mut values, assignment.value, self.placeholder);
+        }
+        for (i, (column, value)) in self.filters.into_iter().enumerate() {
+            sql.push_str(if i == 0 { " WHERE " } else { " AND " });
+            if let Value::Null = value {
+                write!(sql, "{} IS NULL", column).unwrap();
+            } else {
+                write!(sql, "{} = ", column).unwrap();
+                push_value(&mut sql, &mut values, value, self.placeholder);
+            }
+        }
+        Some((sql, values))
+    }
+}
+
+/// Writes `NULL` or the placeholder of `value` to `sql`, binding it in `values`
+fn push_value(sql: &mut String, values: &mut Vec<Value>, value: Value, placeholder: Placeholder) {
+    if let Value::Null = value {
+        sql.push_str("NULL");
+        return;
+    }
+    values.push(value);
+    match placeholder {
+        Placeholder::Question => sql.push('?'),
+        Placeholder::Dollar => write!(sql, "${}", values.len()).unwrap(),
+    }
+}
+
+#[cfg(feature = "sqlx")]
+mod sqlx_impls {
+    use sqlx::encode::IsNull;
+    use sqlx::error::BoxDynError;
+    use sqlx::{Database, Encode, QueryBuilder, Type};
+
+    use super::{UpdateBuilder, Value};
+
+    /// A [`Value`] is bound with the SQL type of its variant.
+    impl<DB: Database> Type<DB> for Value
+    where
+        bool: Type<DB>,
+        i64: Type<DB>,
+        f64: Type<DB>,
+        String: Type<DB>,
+        Vec<u8>: Type<DB>,
+    {
+        fn type_info() -> DB::TypeInfo {
+            String::type_info()
+        }
+
+        fn compatible(ty: &DB::TypeInfo) -> bool {
+            bool::compatible(ty)
+                || i64::compatible(ty)
+                || f64::compatible(ty)
+                || String::compatible(ty)
+                || Vec::<u8>::compatible(ty)
+        }
+    }
+
+    impl<'q, DB: Database> Encode<'q, DB> for Value
+    where
+        bool: Encode<'q, DB> + Type<DB>,
+        i64: Encode<'q, DB> + Type<DB>,
+        f64: Encode<'q, DB> + Type<DB>,
+        String: Encode<'q, DB> + Type<DB>,
+        Vec<u8>: Encode<'q, DB> + Type<DB>,
+    {
+        fn encode(self, buf: &mut DB::ArgumentBuffer) -> Result<IsNull, BoxDynError> {
+            match self {
+                Value::Null => Ok(IsNull::Yes),
+                Value::Bool(val) => val.encode(buf),
+                Value::Int(val) => val.encode(buf),
+                Value::Float(val) => val.encode(buf),
+                Value::Text(val) => val.encode(buf),
+                Value::Bytes(val) => val.encode(buf),
+            }
+        }
+
+        fn encode_by_ref(&self, buf: &mut DB::ArgumentBuffer) -> Result<IsNull, BoxDynError> {
+            match self {
+                Value::Null => Ok(IsNull::Yes),
+                Value::Bool(val) => val.encode_by_ref(buf),
+                Value::Int(val) => val.encode_by_ref(buf),
+                Value::Float(val) => val.encode_by_ref(buf),
+                Value::Text(val) => val.encode_by_ref(buf),
+                Value::Bytes(val) => val.encode_by_ref(buf),
+            }
+        }
+
+        fn produces(&self) -> Option<DB::TypeInfo> {
+            match self {
+                Value::Null => None,
+                Value::Bool(_) => Some(bool::type_info()),
+                Value::Int(_) => Some(i64::type_info()),
+                Value::Float(_) => Some(f64::type_info()),
+                Value::Text(_) => Some(String::type_info()),
+                Value::Bytes(_) => Some(Vec::<u8>::type_info()),
+            }
+        }
+    }
+
+    impl UpdateBuilder {
+        /// Starts a sqlx `QueryBuilder` with the statement, binding its values.
+        ///
+        /// The placeholders are those of `DB`, more SQL such as a `RETURNING` clause can be
+        /// pushed before building the query. Returns `None` if there is nothing to update.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// # use optional_field::Field::*;
+        /// use optional_field::sql::UpdateBuilder;
+        /// use sqlx::Sqlite;
+        ///
+        /// let mut query = UpdateBuilder::new("users")
+        ///     .set("name", &Present(Some("bob")))
+        ///     .set("email", &Present::<String>(None))
+        ///     .filter("id", 7)
+        ///     .into_query_builder::<Sqlite>()
+        ///     .unwrap();
+        /// query.push(" RETURNING id");
+        ///
+        /// assert_eq!(
+        ///     query.sql().as_str(),
+        ///     "UPDATE users SET name = ?, email = NULL WHERE id = ? RETURNING id"
+        /// );
+        /// ```
+        pub fn into_query_builder<DB>(self) -> Option<QueryBuilder<DB>>
+        where
+            DB: Database,
+            DB::Arguments: Default,
+            Value: for<'q> Encode<'q, DB> + Type<DB>,
+        {
+            if self.is_empty() {
+                return None;
+            }
+            let mut query = QueryBuilder::new(format!("UPDATE {} SET ", self.table));
+            for (i, assignment) in self.assignments.into_iter().enumerate() {
+                if i > 0 {
+                    query.push(", ");
+                }
+                query.push(format_args!("{} = ", assignment.column));
+                match assignment.value {
+                    Value::Null => query.push("NULL"),
+                    value => query.push_bind(value),
+                };
+            }
+            for (i, (column, value)) in self.filters.into_iter().enumerate() {
+                query.push(if i == 0 { " WHERE " } else { " AND " });
+                match value {
+                    Value::Null => query.push(format_args!("{} IS NULL", column)),
+                    value => query.push(format_args!("{} = ", column)).push_bind(value),
+                };
+            }
+            Some(query)
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::{Database, Decode, Encode, Type, TypeInfo};
+
+use crate::Field::{self, *};
+use crate::FieldError;
+
+/// A `Field<T>` has the SQL type of `T` and is nullable, like an `Option<T>`.
+impl<T: Type<DB>, DB: Database> Type<DB> for Field<T> {
+    fn type_info() -> DB::TypeInfo {
+        T::type_info()
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        ty.is_null() || T::compatible(ty)
+    }
+}
+
+/// `Present(None)` is bound as `NULL`.
+///
+/// Binding a [`Missing`] field makes the query fail when it is executed, with
+/// [`FieldError::Missing`] as the source of `sqlx::Error::Encode`. The statements of an
+/// [`UpdateBuilder`](crate::sql::UpdateBuilder) only bind the members that are present.
+impl<'q, T, DB: Database> Encode<'q, DB> for Field<T>
+where
+    T: Encode<'q, DB> + Type<DB>,
+{
+    fn encode(self, buf: &mut DB::ArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        match self {
+            Present(Some(val)) => val.encode(buf),
+            Present(None) => Ok(IsNull::Yes),
+            Missing => Err(Box::new(FieldError::Missing)),
+        }
+    }
+
+    fn encode_by_ref(&self, buf: &mut DB::ArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        match self {
+            Present(Some(val)) => val.encode_by_ref(buf),
+            Present(None) => Ok(IsNull::Yes),
+            Missing => Err(Box::new(FieldError::Missing)),
+        }
+    }
+
+    fn produces(&self) -> Option<DB::TypeInfo> {
+        match self {
+            Present(Some(val)) => val.produces(),
+            _ => Some(T::type_info()),
+        }
+    }
+
+    fn size_hint(&self) -> usize {
+        match self {
+            Present(Some(val)) => val.size_hint(),
+            _ => 0,
+        }
+    }
+}
+
+/// A `NULL` column is decoded as `Present(None)`, a column is never [`Missing`].
+impl<'r, T, DB: Database> Decode<'r, DB> for Field<T>
+where
+    T: Decode<'r, DB>,
+{
+    fn decode(value: DB::ValueRef<'r>) -> Result<Self, BoxDynError> {
+        Option::<T>::decode(value).map(Present)
+    }
+}
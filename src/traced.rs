@@ -0,0 +1,304 @@
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Serializer};
+
+use crate::Field::{self, *};
+
+/// A [`Field`] that remembers which source produced its current state.
+///
+/// Useful when layering configs or applying several patches on top of each other: after
+/// merging, every member still knows whether its value came from the defaults, a config file,
+/// the environment and so on.
+///
+/// # Examples
+///
+/// ```
+/// # use optional_field::Field::*;
+/// # use optional_field::TracedField;
+/// let mut port = TracedField::new(Present(Some(80)), "defaults");
+/// port.merge(TracedField::new(Missing, "file"));
+/// port.merge(TracedField::new(Present(Some(8080)), "env"));
+///
+/// assert_eq!(port.field(), &Present(Some(8080)));
+/// assert_eq!(port.source(), Some(&"env"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TracedField<T, S> {
+    field: Field<T>,
+    source: Option<S>,
+}
+
+impl<T, S> TracedField<T, S> {
+    /// Creates a new `TracedField` from `field`, tagged with `source`.
+    pub fn new(field: Field<T>, source: S) -> Self {
+        TracedField {
+            field,
+            source: Some(source),
+        }
+    }
+
+    /// Creates a new `TracedField` from `field` without a source.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use optional_field::Field::*;
+    /// # use optional_field::TracedField;
+    /// let x: TracedField<u8, &str> = TracedField::untraced(Present(None));
+    /// assert_eq!(x.source(), None);
+    /// ```
+    pub fn untraced(field: Field<T>) -> Self {
+        TracedField {
+            field,
+            source: None,
+        }
+    }
+
+    /// Is the value missing?
+    #[inline]
+    pub fn is_missing(&self) -> bool {
+        self.field.is_missing()
+    }
+
+    /// Is the value present?
+    #[inline]
+    pub fn is_present(&self) -> bool {
+        self.field.is_present()
+    }
+
+    /// Returns a reference to the underlying field.
+    pub fn field(&self) -> &Field<T> {
+        &self.field
+    }
+
+    /// Returns a reference to the source that produced the current state, if any.
+    pub fn source(&self) -> Option<&S> {
+        self.source.as_ref()
+    }
+
+    /// Converts into the underlying field, dropping the source.
+    pub fn into_field(self) -> Field<T> {
+        self.field
+    }
+
+    /// Converts into the underlying field and its source.
+    pub fn into_parts(self) -> (Field<T>, Option<S>) {
+        (self.field, self.source)
+    }
+
+    /// Merges `other` on top of `self`.
+    ///
+    /// If `other` is [`Missing`] nothing changes, otherwise both the state and the source of
+    /// `other` replace those of `self`. A [`Present(None)`](Present) therefore wins over an
+    /// existing value, the same way an explicit `null` does in a patch.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use optional_field::Field::*;
+    /// # use optional_field::TracedField;
+    /// let mut name = TracedField::new(Present(Some("bob")), "file");
+    ///
+    /// name.merge(TracedField::new(Missing, "env"));
+    /// assert_eq!(name.source(), Some(&"file"));
+    ///
+    /// name.merge(TracedField::new(Present(None), "cli"));
+    /// assert_eq!(name.field(), &Present(None));
+    /// assert_eq!(name.source(), Some(&"cli"));
+    /// ```
+    pub fn merge(&mut self, other: TracedField<T, S>) {
+        if other.is_present() {
+            *self = other;
+        }
+    }
+
+    /// Merges `field` tagged with `source` on top of `self`.
+    ///
+    /// Shorthand for `self.merge(TracedField::new(field, source))`.
+    pub fn merge_field(&mut self, field: Field<T>, source: S) {
+        self.merge(TracedField::new(field, source));
+    }
+
+    /// Merges `other` on top of `self` and returns the result.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use optional_field::Field::*;
+    /// # use optional_field::TracedField;
+    /// let base = TracedField::new(Present(Some(1)), "defaults");
+    /// let merged = base.merged(TracedField::new(Present(Some(2)), "file"));
+    /// assert_eq!(merged.into_parts(), (Present(Some(2)), Some("file")));
+    /// ```
+    pub fn merged(mut self, other: TracedField<T, S>) -> Self {
+        self.merge(other);
+        self
+    }
+}
+
+impl<T, S> Default for TracedField<T, S> {
+    fn default() -> Self {
+        TracedField::untraced(Missing)
+    }
+}
+
+impl<T, S> From<Field<T>> for TracedField<T, S> {
+    fn from(field: Field<T>) -> Self {
+        TracedField::untraced(field)
+    }
+}
+
+impl<T, S> From<TracedField<T, S>> for Field<T> {
+    fn from(traced: TracedField<T, S>) -> Field<T> {
+        traced.field
+    }
+}
+
+impl<T> Field<T> {
+    /// Tags the field with the `source` it came from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use optional_field::Field::*;
+    /// let x = Present(Some(3)).traced("env");
+    /// assert_eq!(x.source(), Some(&"env"));
+    /// ```
+    pub fn traced<S>(self, source: S) -> TracedField<T, S> {
+        TracedField::new(self, source)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T, S> Serialize for TracedField<T, S>
+where
+    T: Serialize,
+{
+    fn serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+    where
+        Ser: Serializer,
+    {
+        self.field.serialize(serializer)
+    }
+}
+
+/// The state of a field as listed in a [`ProvenanceReport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldState {
+    /// The field is [`Missing`].
+    Missing,
+    /// The field is `Present(None)`.
+    Null,
+    /// The field is `Present(Some(_))`.
+    Value,
+}
+
+impl<T> From<&Field<T>> for FieldState {
+    fn from(field: &Field<T>) -> FieldState {
+        match field {
+            Missing => FieldState::Missing,
+            Present(None) => FieldState::Null,
+            Present(Some(_)) => FieldState::Value,
+        }
+    }
+}
+
+/// A single line of a [`ProvenanceReport`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProvenanceEntry<'a, S> {
+    /// The name the field was added to the report with.
+    pub name: &'a str,
+    /// Whether the merged field is missing, null or has a value.
+    pub state: FieldState,
+    /// The source the merged field was taken from, if any.
+    pub source: Option<&'a S>,
+}
+
+/// Lists each field of a merged struct together with the source that won.
+///
+/// # Examples
+///
+/// ```
+/// # use optional_field::Field::*;
+/// # use optional_field::{FieldState, ProvenanceReport, TracedField};
+/// struct Config {
+///     host: TracedField<String, &'static str>,
+///     port: TracedField<u16, &'static str>,
+/// }
+///
+/// let config = Config {
+///     host: Present(Some("localhost".to_string())).traced("defaults"),
+///     port: Present(Some(8080)).traced("env"),
+/// };
+///
+/// let report = ProvenanceReport::new()
+///     .field("host", &config.host)
+///     .field("port", &config.port);
+///
+/// let port = report.get("port").unwrap();
+/// assert_eq!(port.state, FieldState::Value);
+/// assert_eq!(port.source, Some(&"env"));
+/// assert_eq!(report.to_string(), "host: value (defaults)\nport: value (env)\n");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProvenanceReport<'a, S> {
+    entries: Vec<ProvenanceEntry<'a, S>>,
+}
+
+impl<'a, S> ProvenanceReport<'a, S> {
+    /// Creates an empty report.
+    pub fn new() -> Self {
+        ProvenanceReport {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Adds the field `name` to the report.
+    pub fn field<T>(mut self, name: &'a str, field: &'a TracedField<T, S>) -> Self {
+        self.push(name, field);
+        self
+    }
+
+    /// Adds the field `name` to the report.
+    pub fn push<T>(&mut self, name: &'a str, field: &'a TracedField<T, S>) {
+        self.entries.push(ProvenanceEntry {
+            name,
+            state: FieldState::from(field.field()),
+            source: field.source(),
+        });
+    }
+
+    /// Returns the entry for the field `name`, if it has been added.
+    pub fn get(&self, name: &str) -> Option<&ProvenanceEntry<'a, S>> {
+        self.entries.iter().find(|entry| entry.name == name)
+    }
+
+    /// Iterates over the entries in the order they were added.
+    pub fn iter(&self) -> impl Iterator<Item = &ProvenanceEntry<'a, S>> {
+        self.entries.iter()
+    }
+}
+
+impl<S> Default for ProvenanceReport<'_, S> {
+    fn default() -> Self {
+        ProvenanceReport::new()
+    }
+}
+
+impl<S: fmt::Display> fmt::Display for ProvenanceReport<'_, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.entries {
+            let state = match entry.state {
+                FieldState::Missing => "missing",
+                FieldState::Null => "null",
+                FieldState::Value => "value",
+            };
+            match entry.source {
+                Some(source) => writeln!(f, "{}: {} ({})", entry.name, state, source)?,
+                None => writeln!(f, "{}: {}", entry.name, state)?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+//! TypeScript types for [`Field`] with ts-rs.
+//!
+//! `#[ts(optional)]` only turns members into `name?: T` when their type claims to be an `Option`,
+//! through the `TS::IS_OPTION` constant, the `IsOption` marker trait and an `OptionInnerType`
+//! naming the inner type. ts-rs hides these from its documentation and may change them in any
+//! release, so `Field<T>` claiming them is why ts-rs is pinned to an exact version.
+
+use ts_rs::{IsOption, TypeVisitor, TS};
+
+use crate::Field;
+
+/// A `Field<T>` is typed like an `Option<T>`, as `T | null`.
+///
+/// Members of structs using `serde_optional_fields` may be omitted and have a default, so ts-rs
+/// marks them optional: `name?: T | null`. `#[ts(optional)]` can be used to emit `name?: T`.
+impl<T: TS> TS for Field<T> {
+    type WithoutGenerics = Self;
+    type OptionInnerType = T;
+    const IS_OPTION: bool = true;
+
+    fn name() -> String {
+        <Option<T>>::name()
+    }
+
+    fn inline() -> String {
+        <Option<T>>::inline()
+    }
+
+    fn visit_dependencies(v: &mut impl TypeVisitor)
+    where
+        Self: 'static,
+    {
+        <Option<T>>::visit_dependencies(v);
+    }
+
+    fn visit_generics(v: &mut impl TypeVisitor)
+    where
+        Self: 'static,
+    {
+        <Option<T>>::visit_generics(v);
+    }
+
+    fn decl() -> String {
+        panic!("{} cannot be declared", Self::name())
+    }
+
+    fn decl_concrete() -> String {
+        panic!("{} cannot be declared", Self::name())
+    }
+
+    fn inline_flattened() -> String {
+        panic!("{} cannot be flattened", Self::name())
+    }
+}
+
+impl<T> IsOption for Field<T> {}
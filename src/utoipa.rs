@@ -0,0 +1,33 @@
+use std::borrow::Cow;
+
+use utoipa::openapi::schema::{ObjectBuilder, OneOfBuilder, Type};
+use utoipa::openapi::{RefOr, Schema};
+use utoipa::{PartialSchema, ToSchema};
+
+use crate::Field;
+
+/// A `Field` is documented like an `Option`: the value's schema, also allowing `null`.
+///
+/// `#[derive(ToSchema)]` composes generic member types with a hidden utoipa trait, which this
+/// crate does not implement as it is not part of utoipa's public API. `Field` members of a
+/// deriving struct are therefore documented through `#[schema(value_type = Option<T>)]`, which
+/// `#[serde_optional_fields]` adds to them.
+impl<T: PartialSchema> PartialSchema for Field<T> {
+    fn schema() -> RefOr<Schema> {
+        OneOfBuilder::new()
+            .item(ObjectBuilder::new().schema_type(Type::Null))
+            .item(T::schema())
+            .into()
+    }
+}
+
+/// The schema is named `Field_<T>`, so that registering it as a component keeps the schema of `T`.
+impl<T: ToSchema> ToSchema for Field<T> {
+    fn name() -> Cow<'static, str> {
+        Cow::Owned(format!("Field_{}", T::name()))
+    }
+
+    fn schemas(schemas: &mut Vec<(String, RefOr<Schema>)>) {
+        T::schemas(schemas);
+    }
+}
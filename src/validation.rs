@@ -0,0 +1,805 @@
+//! Validation of [`Field`] values that knows the difference between missing and null.
+//!
+//! Rules are small values implementing [`Rule`] which check a single `Field`. They can be used
+//! on their own, or through `#[derive(ValidateFields)]` which validates every annotated member of
+//! a struct and collects all violations together with the wire path of the offending member.
+//!
+//! # Examples
+//!
+//! ```
+//! # use optional_field::Field::*;
+//! use optional_field::validation::{if_present, len, non_null, Rule, RuleExt, Violation};
+//!
+//! // May be missing, must not be null and must be at most 5 characters long.
+//! let rule = non_null().and(if_present(len(None, Some(5))));
+//!
+//! assert_eq!(rule.check(&Missing::<String>), Ok(()));
+//! assert_eq!(rule.check(&Present::<String>(None)), Err(Violation::Null));
+//! assert!(rule.check(&Present(Some("too long".to_string()))).is_err());
+//! ```
+//!
+//! The derive reads its rules from `#[field(...)]` attributes and uses the serde name of each
+//! member in the reported paths:
+//!
+//! ```
+//! # use optional_field::Field::{self, *};
+//! use optional_field::ValidateFields;
+//!
+//! #[derive(ValidateFields)]
+//! struct Address {
+//!     #[field(required)]
+//!     city: Field<String>,
+//! }
+//!
+//! #[derive(ValidateFields)]
+//! struct UserPatch {
+//!     #[field(non_null, len(max = 50))]
+//!     name: Field<String>,
+//!     #[field(nested)]
+//!     address: Field<Address>,
+//! }
+//!
+//! let patch = UserPatch {
+//!     name: Present(None),
+//!     address: Present(Some(Address { city: Missing })),
+//! };
+//!
+//! let errors = patch.validate_fields().unwrap_err();
+//! assert_eq!(errors.paths().collect::<Vec<_>>(), vec!["name", "address.city"]);
+//! ```
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::error::Error;
+use std::fmt;
+
+use crate::Field::{self, *};
+use crate::FieldError;
+
+/// Why a field failed validation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /// The field is [`Missing`] but is required.
+    Missing,
+    /// The field is [`Present(None)`](Present) but must not be null.
+    Null,
+    /// The value is empty but must not be.
+    Empty,
+    /// The length of the value is outside of the allowed range.
+    Length {
+        min: Option<usize>,
+        max: Option<usize>,
+        actual: usize,
+    },
+    /// A custom rule failed with the given message.
+    Custom(Cow<'static, str>),
+}
+
+impl From<FieldError> for Violation {
+    fn from(err: FieldError) -> Violation {
+        match err {
+            FieldError::Missing => Violation::Missing,
+            FieldError::Null => Violation::Null,
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::Missing => write!(f, "is required"),
+            Violation::Null => write!(f, "must not be null"),
+            Violation::Empty => write!(f, "must not be empty"),
+            Violation::Length {
+                min: Some(min),
+                max: Some(max),
+                ..
+            } => write!(f, "length must be between {} and {}", min, max),
+            Violation::Length { min: Some(min), .. } => {
+                write!(f, "length must be at least {}", min)
+            }
+            Violation::Length { max: Some(max), .. } => {
+                write!(f, "length must be at most {}", max)
+            }
+            Violation::Length { .. } => write!(f, "has an invalid length"),
+            Violation::Custom(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+/// A single violation together with the wire path of the field it was found on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    pub path: String,
+    pub violation: Violation,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` {}", self.path, self.violation)
+    }
+}
+
+impl Error for ValidationError {}
+
+/// All violations found while validating a value.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ValidationErrors {
+    errors: Vec<ValidationError>,
+}
+
+impl ValidationErrors {
+    /// Creates an empty list of errors.
+    pub fn new() -> Self {
+        ValidationErrors::default()
+    }
+
+    /// Records `violation` for the field at `path`.
+    pub fn push(&mut self, path: impl Into<String>, violation: Violation) {
+        self.errors.push(ValidationError {
+            path: path.into(),
+            violation,
+        });
+    }
+
+    /// Checks `field` against `rule`, recording a violation for `path` if it fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use optional_field::Field::*;
+    /// use optional_field::validation::{required, ValidationErrors};
+    ///
+    /// let mut errors = ValidationErrors::new();
+    /// errors.check("name", &Missing::<String>, &required());
+    /// assert_eq!(errors.paths().collect::<Vec<_>>(), vec!["name"]);
+    /// ```
+    pub fn check<T, R>(&mut self, path: &str, field: &Field<T>, rule: &R)
+    where
+        R: Rule<T> + ?Sized,
+    {
+        if let Err(violation) = rule.check(field) {
+            self.push(path, violation);
+        }
+    }
+
+    /// Moves all errors of `other` into `self`.
+    pub fn extend(&mut self, other: ValidationErrors) {
+        self.errors.extend(other.errors);
+    }
+
+    /// Returns `true` if no violation has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Returns the number of recorded violations.
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    /// Iterates over the recorded violations.
+    pub fn iter(&self) -> std::slice::Iter<'_, ValidationError> {
+        self.errors.iter()
+    }
+
+    /// Iterates over the paths of the fields with violations.
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.errors.iter().map(|error| error.path.as_str())
+    }
+
+    /// Returns `Ok(())` if no violation has been recorded, otherwise `Err(self)`.
+    pub fn into_result(self) -> Result<(), ValidationErrors> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, error) in self.errors.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", error)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ValidationErrors {}
+
+impl IntoIterator for ValidationErrors {
+    type Item = ValidationError;
+    type IntoIter = std::vec::IntoIter<ValidationError>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a ValidationErrors {
+    type Item = &'a ValidationError;
+    type IntoIter = std::slice::Iter<'a, ValidationError>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors.iter()
+    }
+}
+
+/// Joins the wire path `prefix` of a parent value with the `name` of one of its members.
+///
+/// # Examples
+///
+/// ```
+/// # use optional_field::validation::join_path;
+/// assert_eq!(join_path("", "name"), "name");
+/// assert_eq!(join_path("address", "city"), "address.city");
+/// ```
+pub fn join_path(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", prefix, name)
+    }
+}
+
+/// A check on a single [`Field`].
+pub trait Rule<T> {
+    /// Checks `field`, returning the violation if it does not satisfy the rule.
+    fn check(&self, field: &Field<T>) -> Result<(), Violation>;
+}
+
+/// Combinators for [`Rule`]s.
+pub trait RuleExt: Sized {
+    /// Combines two rules, checking `other` only if `self` succeeds.
+    fn and<R>(self, other: R) -> And<Self, R> {
+        And(self, other)
+    }
+}
+
+impl<R> RuleExt for R {}
+
+impl<T, F> Rule<T> for F
+where
+    F: Fn(&Field<T>) -> Result<(), Violation>,
+{
+    fn check(&self, field: &Field<T>) -> Result<(), Violation> {
+        self(field)
+    }
+}
+
+/// Rule requiring the field to be [`Present(Some(_))`](Present), see [`required`].
+#[derive(Debug, Clone, Copy)]
+pub struct Required;
+
+/// The field must be present and not null.
+///
+/// # Examples
+///
+/// ```
+/// # use optional_field::Field::*;
+/// use optional_field::validation::{required, Rule, Violation};
+///
+/// assert_eq!(required().check(&Missing::<u8>), Err(Violation::Missing));
+/// assert_eq!(required().check(&Present::<u8>(None)), Err(Violation::Null));
+/// assert_eq!(required().check(&Present(Some(1))), Ok(()));
+/// ```
+pub fn required() -> Required {
+    Required
+}
+
+impl<T> Rule<T> for Required {
+    fn check(&self, field: &Field<T>) -> Result<(), Violation> {
+        match field {
+            Missing => Err(Violation::Missing),
+            Present(None) => Err(Violation::Null),
+            Present(Some(_)) => Ok(()),
+        }
+    }
+}
+
+/// Rule rejecting [`Present(None)`](Present), see [`non_null`].
+#[derive(Debug, Clone, Copy)]
+pub struct NonNull;
+
+/// The field may be missing, but must not be null.
+///
+/// # Examples
+///
+/// ```
+/// # use optional_field::Field::*;
+/// use optional_field::validation::{non_null, Rule, Violation};
+///
+/// assert_eq!(non_null().check(&Missing::<u8>), Ok(()));
+/// assert_eq!(non_null().check(&Present::<u8>(None)), Err(Violation::Null));
+/// assert_eq!(non_null().check(&Present(Some(1))), Ok(()));
+/// ```
+pub fn non_null() -> NonNull {
+    NonNull
+}
+
+impl<T> Rule<T> for NonNull {
+    fn check(&self, field: &Field<T>) -> Result<(), Violation> {
+        match field {
+            Present(None) => Err(Violation::Null),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Rule rejecting empty values, see [`non_empty`].
+#[derive(Debug, Clone, Copy)]
+pub struct NonEmpty;
+
+/// If the field has a value, it must not be empty.
+///
+/// # Examples
+///
+/// ```
+/// # use optional_field::Field::*;
+/// use optional_field::validation::{non_empty, Rule, Violation};
+///
+/// assert_eq!(non_empty().check(&Missing::<String>), Ok(()));
+/// assert_eq!(non_empty().check(&Present(Some(String::new()))), Err(Violation::Empty));
+/// assert_eq!(non_empty().check(&Present(Some(vec![1]))), Ok(()));
+/// ```
+pub fn non_empty() -> NonEmpty {
+    NonEmpty
+}
+
+impl<T: HasLength> Rule<T> for NonEmpty {
+    fn check(&self, field: &Field<T>) -> Result<(), Violation> {
+        match field {
+            Present(Some(val)) if val.length() == 0 => Err(Violation::Empty),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Rule limiting the length of values, see [`len`].
+#[derive(Debug, Clone, Copy)]
+pub struct Len {
+    min: Option<usize>,
+    max: Option<usize>,
+}
+
+/// If the field has a value, its length must be within `min` and `max` (inclusive).
+///
+/// The length of strings is counted in characters.
+///
+/// # Examples
+///
+/// ```
+/// # use optional_field::Field::*;
+/// use optional_field::validation::{len, Rule};
+///
+/// let rule = len(Some(1), Some(3));
+/// assert!(rule.check(&Present(Some("abc"))).is_ok());
+/// assert!(rule.check(&Present(Some("abcd"))).is_err());
+/// assert!(rule.check(&Present::<&str>(None)).is_ok());
+/// ```
+pub fn len(min: Option<usize>, max: Option<usize>) -> Len {
+    Len { min, max }
+}
+
+impl<T: HasLength> Rule<T> for Len {
+    fn check(&self, field: &Field<T>) -> Result<(), Violation> {
+        if let Present(Some(val)) = field {
+            let actual = val.length();
+            let too_short = self.min.is_some_and(|min| actual < min);
+            let too_long = self.max.is_some_and(|max| actual > max);
+            if too_short || too_long {
+                return Err(Violation::Length {
+                    min: self.min,
+                    max: self.max,
+                    actual,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Rule only applied to present fields, see [`if_present`].
+#[derive(Debug, Clone, Copy)]
+pub struct IfPresent<R>(R);
+
+/// Checks `rule` only if the field is present, accepting [`Missing`].
+///
+/// # Examples
+///
+/// ```
+/// # use optional_field::Field::*;
+/// use optional_field::validation::{if_present, required, Rule, Violation};
+///
+/// let rule = if_present(required());
+/// assert_eq!(rule.check(&Missing::<u8>), Ok(()));
+/// assert_eq!(rule.check(&Present::<u8>(None)), Err(Violation::Null));
+/// ```
+pub fn if_present<R>(rule: R) -> IfPresent<R> {
+    IfPresent(rule)
+}
+
+impl<T, R: Rule<T>> Rule<T> for IfPresent<R> {
+    fn check(&self, field: &Field<T>) -> Result<(), Violation> {
+        match field {
+            Missing => Ok(()),
+            _ => self.0.check(field),
+        }
+    }
+}
+
+/// Two rules checked one after the other, see [`RuleExt::and`].
+#[derive(Debug, Clone, Copy)]
+pub struct And<A, B>(A, B);
+
+impl<T, A: Rule<T>, B: Rule<T>> Rule<T> for And<A, B> {
+    fn check(&self, field: &Field<T>) -> Result<(), Violation> {
+        self.0.check(field)?;
+        self.1.check(field)
+    }
+}
+
+/// Values with a length, used by [`non_empty`] and [`len`].
+pub trait HasLength {
+    fn length(&self) -> usize;
+}
+
+impl HasLength for str {
+    fn length(&self) -> usize {
+        self.chars().count()
+    }
+}
+
+impl HasLength for String {
+    fn length(&self) -> usize {
+        self.as_str().length()
+    }
+}
+
+impl<T> HasLength for [T] {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> HasLength for Vec<T> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> HasLength for VecDeque<T> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K, V, S> HasLength for HashMap<K, V, S> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T, S> HasLength for HashSet<T, S> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K, V> HasLength for BTreeMap<K, V> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> HasLength for BTreeSet<T> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T: HasLength + ?Sized> HasLength for &T {
+    fn length(&self) -> usize {
+        (**self).length()
+    }
+}
+
+impl<T: HasLength + ?Sized> HasLength for Box<T> {
+    fn length(&self) -> usize {
+        (**self).length()
+    }
+}
+
+/// The operation a value is validated for.
+///
+/// DTOs are often shared between creating and updating a resource, where a member may be
+/// required on create but may be left out on update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// The resource is created, selected by `required_on = "create"`.
+    Create,
+    /// The resource is updated, selected by `required_on = "update"`.
+    Update,
+}
+
+/// Rule requiring the field only for some modes, see [`required_on`].
+#[derive(Debug, Clone, Copy)]
+pub struct RequiredOn<'a> {
+    modes: &'a [Mode],
+    mode: Option<Mode>,
+}
+
+/// The field must be present and not null when validating for one of `modes`.
+///
+/// `mode` is the mode currently validated for, or `None` when validating without a mode, in
+/// which case the rule always succeeds.
+///
+/// # Examples
+///
+/// ```
+/// # use optional_field::Field::*;
+/// use optional_field::validation::{required_on, Mode, Rule, Violation};
+///
+/// let field = Missing::<u8>;
+/// assert_eq!(required_on(&[Mode::Create], Some(Mode::Create)).check(&field), Err(Violation::Missing));
+/// assert_eq!(required_on(&[Mode::Create], Some(Mode::Update)).check(&field), Ok(()));
+/// assert_eq!(required_on(&[Mode::Create], None).check(&field), Ok(()));
+/// ```
+pub fn required_on(modes: &[Mode], mode: Option<Mode>) -> RequiredOn<'_> {
+    RequiredOn { modes, mode }
+}
+
+impl<T> Rule<T> for RequiredOn<'_> {
+    fn check(&self, field: &Field<T>) -> Result<(), Violation> {
+        match self.mode {
+            Some(mode) if self.modes.contains(&mode) => required().check(field),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Types whose members can be validated, usually implemented with `#[derive(ValidateFields)]`.
+///
+/// The derive accepts the following `#[field(...)]` attributes on `Field` members:
+///
+/// * `required`: must be present and not null
+/// * `required_on = "create"`: must be present and not null when validating for
+///   [`Mode::Create`] (or `"update"` for [`Mode::Update`])
+/// * `non_null`: may be missing but must not be null
+/// * `non_empty`: if there is a value, it must not be empty
+/// * `len(min = 1, max = 50)`: if there is a value, its length must be within the bounds
+/// * `custom = "path::to::rule"`: a function `fn(&Field<T>) -> Result<(), Violation>`
+///
+/// Any member, `Field` or not, can be marked `nested` to validate it with its own
+/// `ValidateFields` implementation. Paths use the serde name of each member, honouring
+/// `rename` and `rename_all`.
+///
+/// The other derives of this crate read the same `#[field(...)]` attribute, so keys such as
+/// `column` are accepted and left to them, while keys no derive reads are rejected.
+///
+/// # Examples
+///
+/// ```
+/// # use optional_field::Field::{self, *};
+/// use optional_field::validation::{Mode, Violation};
+/// use optional_field::ValidateFields;
+///
+/// #[derive(ValidateFields)]
+/// struct UserDto {
+///     #[field(required_on = "create")]
+///     email: Field<String>,
+///     #[field(non_null)]
+///     name: Field<String>,
+/// }
+///
+/// let dto = UserDto { email: Missing, name: Missing };
+///
+/// assert!(dto.validate_for(Mode::Update).is_ok());
+///
+/// let errors = dto.validate_for(Mode::Create).unwrap_err();
+/// let error = errors.iter().next().unwrap();
+/// assert_eq!((error.path.as_str(), &error.violation), ("email", &Violation::Missing));
+/// ```
+pub trait ValidateFields {
+    /// Validates `self`, found at `path`, for `mode`, recording all violations in `errors`.
+    ///
+    /// With a `mode` of `None` only the rules that apply to every mode are checked.
+    fn validate_fields_at(&self, mode: Option<Mode>, path: &str, errors: &mut ValidationErrors);
+
+    /// Validates `self` against the rules that apply to every mode, returning all violations.
+    fn validate_fields(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        self.validate_fields_at(None, "", &mut errors);
+        errors.into_result()
+    }
+
+    /// Validates `self` for `mode`, returning all violations.
+    fn validate_for(&self, mode: Mode) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        self.validate_fields_at(Some(mode), "", &mut errors);
+        errors.into_result()
+    }
+}
+
+impl<T: ValidateFields> ValidateFields for Field<T> {
+    fn validate_fields_at(&self, mode: Option<Mode>, path: &str, errors: &mut ValidationErrors) {
+        if let Present(Some(val)) = self {
+            val.validate_fields_at(mode, path, errors);
+        }
+    }
+}
+
+impl<T: ValidateFields> ValidateFields for Option<T> {
+    fn validate_fields_at(&self, mode: Option<Mode>, path: &str, errors: &mut ValidationErrors) {
+        if let Some(val) = self {
+            val.validate_fields_at(mode, path, errors);
+        }
+    }
+}
+
+impl<T: ValidateFields + ?Sized> ValidateFields for Box<T> {
+    fn validate_fields_at(&self, mode: Option<Mode>, path: &str, errors: &mut ValidationErrors) {
+        (**self).validate_fields_at(mode, path, errors);
+    }
+}
+
+impl<T: ValidateFields> ValidateFields for [T] {
+    fn validate_fields_at(&self, mode: Option<Mode>, path: &str, errors: &mut ValidationErrors) {
+        for (i, val) in self.iter().enumerate() {
+            val.validate_fields_at(mode, &format!("{}[{}]", path, i), errors);
+        }
+    }
+}
+
+impl<T: ValidateFields> ValidateFields for Vec<T> {
+    fn validate_fields_at(&self, mode: Option<Mode>, path: &str, errors: &mut ValidationErrors) {
+        self.as_slice().validate_fields_at(mode, path, errors);
+    }
+}
+
+/// Conversion of a [`Field`] into a member of a model, used by `#[derive(TryIntoModel)]`.
+///
+/// Any type can be created from a `Present(Some(_))` field, an `Option` can also be created
+/// from a `Present(None)` field.
+///
+/// # Examples
+///
+/// ```
+/// # use optional_field::Field::*;
+/// use optional_field::validation::{FromField, Violation};
+///
+/// assert_eq!(u8::from_field(Present(Some(1))), Ok(1));
+/// assert_eq!(u8::from_field(Present(None)), Err(Violation::Null));
+/// assert_eq!(Option::<u8>::from_field(Present::<u8>(None)), Ok(None));
+/// assert_eq!(Option::<u8>::from_field(Missing::<u8>), Err(Violation::Missing));
+/// ```
+///
+/// The derive converts a whole patch into a model, reporting every missing and null member at
+/// once instead of panicking on the first one:
+///
+/// ```
+/// # use std::convert::TryFrom;
+/// # use optional_field::Field::{self, *};
+/// use optional_field::TryIntoModel;
+///
+/// struct User {
+///     name: String,
+///     email: String,
+///     nickname: Option<String>,
+/// }
+///
+/// #[derive(TryIntoModel)]
+/// #[field(model = "User")]
+/// struct UserPatch {
+///     name: Field<String>,
+///     email: Field<String>,
+///     nickname: Field<String>,
+/// }
+///
+/// let patch = UserPatch { name: Present(None), email: Missing, nickname: Present(None) };
+/// let errors = User::try_from(patch).err().unwrap();
+/// assert_eq!(errors.paths().collect::<Vec<_>>(), vec!["name", "email"]);
+///
+/// let patch = UserPatch {
+///     name: Present(Some("Bob".to_string())),
+///     email: Present(Some("bob@example.com".to_string())),
+///     nickname: Present(None),
+/// };
+/// let user = User::try_from(patch).ok().unwrap();
+/// assert_eq!(user.nickname, None);
+/// ```
+pub trait FromField<T>: Sized {
+    /// Converts `field`, returning the violation if the field is missing or null.
+    fn from_field(field: Field<T>) -> Result<Self, Violation>;
+}
+
+impl<T> FromField<T> for T {
+    fn from_field(field: Field<T>) -> Result<Self, Violation> {
+        Ok(field.try_unwrap()?)
+    }
+}
+
+impl<T> FromField<T> for Option<T> {
+    fn from_field(field: Field<T>) -> Result<Self, Violation> {
+        Ok(field.try_unwrap_present()?)
+    }
+}
+
+#[cfg(feature = "validator")]
+mod validator_impls {
+    //! Lets `#[derive(validator::Validate)]` structs use their usual rules on `Field` members.
+    //!
+    //! As with `Option`, rules other than `required` only look at `Present(Some(_))` values.
+
+    use std::borrow::Cow;
+
+    use validator::{
+        Validate, ValidateEmail, ValidateLength, ValidateRange, ValidateRequired, ValidateUrl,
+        ValidationErrors,
+    };
+
+    use crate::Field::{self, *};
+
+    impl<T: Validate> Validate for Field<T> {
+        fn validate(&self) -> Result<(), ValidationErrors> {
+            match self {
+                Present(Some(val)) => val.validate(),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    impl<T: ValidateLength<u64>> ValidateLength<u64> for Field<T> {
+        fn length(&self) -> Option<u64> {
+            match self {
+                Present(Some(val)) => val.length(),
+                _ => None,
+            }
+        }
+    }
+
+    impl<T, U: ValidateRange<T>> ValidateRange<T> for Field<U> {
+        fn greater_than(&self, max: T) -> Option<bool> {
+            match self {
+                Present(Some(val)) => val.greater_than(max),
+                _ => None,
+            }
+        }
+
+        fn less_than(&self, min: T) -> Option<bool> {
+            match self {
+                Present(Some(val)) => val.less_than(min),
+                _ => None,
+            }
+        }
+    }
+
+    impl<T: ValidateEmail> ValidateEmail for Field<T> {
+        fn as_email_string(&self) -> Option<Cow<'_, str>> {
+            match self {
+                Present(Some(val)) => val.as_email_string(),
+                _ => None,
+            }
+        }
+    }
+
+    impl<T: ValidateUrl> ValidateUrl for Field<T> {
+        fn as_url_string(&self) -> Option<Cow<'_, str>> {
+            match self {
+                Present(Some(val)) => val.as_url_string(),
+                _ => None,
+            }
+        }
+    }
+
+    impl<T> ValidateRequired for Field<T> {
+        fn is_some(&self) -> bool {
+            self.has_value()
+        }
+    }
+}
@@ -0,0 +1,627 @@
+//! An explicit `!missing` tag for [`Field`] values in YAML.
+//!
+//! Members of a [`serde_optional_fields`](crate::serde_optional_fields) struct already round-trip
+//! through `serde_yaml` and `serde_norway`: an absent key is [`Missing`], while `~`, `null` and an
+//! empty value are `Present(None)`. Inside sequences there is no key to leave out, so this module
+//! writes [`Missing`] as a value tagged `!missing`:
+//!
+//! ```yaml
+//! replicas: [3, !missing null, ~]
+//! ```
+//!
+//! [`serialize`] and [`deserialize`] are used with `#[serde(with = "optional_field::yaml")]` on a
+//! `Field<T>` member, [`seq`] does the same for a `Vec<Field<T>>` and [`Tagged`] wraps a `Field<T>`
+//! in any other container. The tag is read back both with and without a `null` value, and every
+//! other value is read by `T` as usual: a plain `1.0` is a `Field<String>` or a `Field<f64>`
+//! alike, and the `!Variant` tags of enums are kept.
+//!
+//! # Examples
+//!
+//! ```
+//! use optional_field::Field::{self, *};
+//! use optional_field::serde_optional_fields;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[serde_optional_fields]
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Overlay {
+//!     image: Field<String>,
+//!     #[serde(with = "optional_field::yaml::seq")]
+//!     replicas: Vec<Field<u8>>,
+//! }
+//!
+//! let overlay = Overlay {
+//!     image: Missing,
+//!     replicas: vec![Present(Some(3)), Missing, Present(None)],
+//! };
+//! let yaml = serde_yaml::to_string(&overlay).unwrap();
+//!
+//! assert_eq!(yaml, "replicas:\n- 3\n- !missing null\n- null\n");
+//! assert_eq!(serde_yaml::from_str::<Overlay>(&yaml).unwrap(), overlay);
+//! ```
+
+use serde::de::value::StrDeserializer;
+use serde::de::{
+    self, DeserializeSeed, EnumAccess, IgnoredAny, IntoDeserializer, MapAccess, SeqAccess,
+    VariantAccess, Visitor,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cell::Cell;
+use std::fmt;
+
+use crate::Field::{self, *};
+
+/// The tag [`Missing`] is written with.
+pub const TAG: &str = "missing";
+
+/// Serializes [`Missing`] as `!missing null`.
+pub fn serialize<T, S>(field: &Field<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Serialize,
+    S: Serializer,
+{
+    match field {
+        Present(Some(val)) => val.serialize(serializer),
+        Present(None) => serializer.serialize_none(),
+        Missing => serializer.serialize_newtype_variant("Field", 0, TAG, &()),
+    }
+}
+
+/// Deserializes a value tagged `!missing` as [`Missing`].
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Field<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    let missing = Cell::new(false);
+    let untag = Untag {
+        deserializer,
+        missing: &missing,
+    };
+    match Option::<T>::deserialize(untag) {
+        Err(_) if missing.get() => Ok(Missing),
+        value => value.map(Present),
+    }
+}
+
+/// [`serialize`] and [`deserialize`] for every `Field<T>` of a `Vec<Field<T>>`.
+///
+/// To be used with `#[serde(with = "optional_field::yaml::seq")]`.
+pub mod seq {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{Tagged, TaggedRef};
+    use crate::Field;
+
+    /// Serializes every [`Missing`](crate::Field::Missing) element as `!missing null`.
+    pub fn serialize<T, S>(fields: &[Field<T>], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        serializer.collect_seq(fields.iter().map(TaggedRef))
+    }
+
+    /// Deserializes every element tagged `!missing` as [`Missing`](crate::Field::Missing).
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Vec<Field<T>>, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        let fields = Vec::<Tagged<T>>::deserialize(deserializer)?;
+        Ok(fields.into_iter().map(|field| field.0).collect())
+    }
+}
+
+/// A `Field<T>` using [`serialize`] and [`deserialize`], for use in maps and other containers.
+///
+/// # Examples
+///
+/// ```
+/// # use optional_field::Field::*;
+/// # use optional_field::yaml::Tagged;
+/// # use std::collections::BTreeMap;
+/// let labels: BTreeMap<String, Tagged<String>> =
+///     serde_yaml::from_str("team: core\ntier: !missing").unwrap();
+///
+/// assert_eq!(labels["team"].0, Present(Some("core".to_string())));
+/// assert_eq!(labels["tier"].0, Missing);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Tagged<T>(pub Field<T>);
+
+impl<T> From<Field<T>> for Tagged<T> {
+    fn from(field: Field<T>) -> Self {
+        Tagged(field)
+    }
+}
+
+impl<T> From<Tagged<T>> for Field<T> {
+    fn from(tagged: Tagged<T>) -> Self {
+        tagged.0
+    }
+}
+
+impl<T: Serialize> Serialize for Tagged<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(&self.0, serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Tagged<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer).map(Tagged)
+    }
+}
+
+struct TaggedRef<'a, T>(&'a Field<T>);
+
+impl<T: Serialize> Serialize for TaggedRef<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(self.0, serializer)
+    }
+}
+
+/// Hands a tag that has already been read on to the deserializer of `T`.
+struct Retagged<V> {
+    tag: String,
+    variant: V,
+}
+
+impl<'de, V: VariantAccess<'de>> EnumAccess<'de> for Retagged<V> {
+    type Error = V::Error;
+    type Variant = V;
+
+    fn variant_seed<S: DeserializeSeed<'de>>(self, seed: S) -> Result<(S::Value, V), V::Error> {
+        let tag = seed.deserialize(self.tag.into_deserializer())?;
+        Ok((tag, self.variant))
+    }
+}
+
+/// Sets `missing` and returns the error `T` is stopped with when it meets the `!missing` tag.
+fn found<E: de::Error>(missing: &Cell<bool>) -> E {
+    missing.set(true);
+    E::custom(format_args!("unexpected !{} tag", TAG))
+}
+
+/// Deserializes a value for `T`, setting `missing` instead when it is tagged `!missing`.
+///
+/// YAML deserializers only report tags to `deserialize_any` and `deserialize_enum`. Scalars are
+/// read through `deserialize_enum`, which passes an untagged scalar on to the method `T` asked
+/// for, so that a plain `123` stays readable as a string. Sequences and mappings cannot be read as
+/// enums without a tag and are read through `deserialize_any`, which keeps them as they are.
+struct Untag<'a, D> {
+    deserializer: D,
+    missing: &'a Cell<bool>,
+}
+
+impl<'a, 'de, D: Deserializer<'de>> Untag<'a, D> {
+    fn scalar<V: Visitor<'de>>(self, method: Method, visitor: V) -> Result<V::Value, D::Error> {
+        let visitor = Scalar {
+            method,
+            visitor,
+            missing: self.missing,
+        };
+        self.deserializer.deserialize_enum("Field", &[TAG], visitor)
+    }
+
+    fn tags<V>(&self, visitor: V) -> Tags<'a, V> {
+        Tags {
+            visitor,
+            missing: self.missing,
+        }
+    }
+}
+
+macro_rules! deserialize_scalar {
+    ($($method:ident => $kind:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, D::Error> {
+                self.scalar(Method::$kind, visitor)
+            }
+        )*
+    };
+}
+
+macro_rules! deserialize_tagged_any {
+    ($($method:ident($($arg:ident: $ty:ty),*),)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(
+                self,
+                $($arg: $ty,)*
+                visitor: V,
+            ) -> Result<V::Value, D::Error> {
+                $(let _ = $arg;)*
+                self.deserialize_any(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for Untag<'_, D> {
+    type Error = D::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, D::Error> {
+        let visitor = self.tags(visitor);
+        self.deserializer.deserialize_any(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, D::Error> {
+        let visitor = self.tags(visitor);
+        self.deserializer.deserialize_option(visitor)
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        self.scalar(Method::UnitStruct(name), visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        let visitor = self.tags(visitor);
+        self.deserializer.deserialize_newtype_struct(name, visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        let visitor = self.tags(visitor);
+        self.deserializer.deserialize_enum(name, variants, visitor)
+    }
+
+    deserialize_scalar! {
+        deserialize_bool => Bool,
+        deserialize_i8 => I8,
+        deserialize_i16 => I16,
+        deserialize_i32 => I32,
+        deserialize_i64 => I64,
+        deserialize_i128 => I128,
+        deserialize_u8 => U8,
+        deserialize_u16 => U16,
+        deserialize_u32 => U32,
+        deserialize_u64 => U64,
+        deserialize_u128 => U128,
+        deserialize_f32 => F32,
+        deserialize_f64 => F64,
+        deserialize_char => Char,
+        deserialize_str => Str,
+        deserialize_string => String,
+        deserialize_bytes => Bytes,
+        deserialize_byte_buf => ByteBuf,
+        deserialize_unit => Unit,
+        deserialize_identifier => Identifier,
+    }
+
+    deserialize_tagged_any! {
+        deserialize_seq(),
+        deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+        deserialize_map(),
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_ignored_any(),
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.deserializer.is_human_readable()
+    }
+}
+
+/// The `deserialize_*` method `T` asked for a scalar with.
+#[derive(Clone, Copy)]
+enum Method {
+    Bool,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    F32,
+    F64,
+    Char,
+    Str,
+    String,
+    Bytes,
+    ByteBuf,
+    Unit,
+    UnitStruct(&'static str),
+    Identifier,
+}
+
+impl Method {
+    fn call<'de, D, V>(self, deserializer: D, visitor: V) -> Result<V::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+        V: Visitor<'de>,
+    {
+        match self {
+            Method::Bool => deserializer.deserialize_bool(visitor),
+            Method::I8 => deserializer.deserialize_i8(visitor),
+            Method::I16 => deserializer.deserialize_i16(visitor),
+            Method::I32 => deserializer.deserialize_i32(visitor),
+            Method::I64 => deserializer.deserialize_i64(visitor),
+            Method::I128 => deserializer.deserialize_i128(visitor),
+            Method::U8 => deserializer.deserialize_u8(visitor),
+            Method::U16 => deserializer.deserialize_u16(visitor),
+            Method::U32 => deserializer.deserialize_u32(visitor),
+            Method::U64 => deserializer.deserialize_u64(visitor),
+            Method::U128 => deserializer.deserialize_u128(visitor),
+            Method::F32 => deserializer.deserialize_f32(visitor),
+            Method::F64 => deserializer.deserialize_f64(visitor),
+            Method::Char => deserializer.deserialize_char(visitor),
+            Method::Str => deserializer.deserialize_str(visitor),
+            Method::String => deserializer.deserialize_string(visitor),
+            Method::Bytes => deserializer.deserialize_bytes(visitor),
+            Method::ByteBuf => deserializer.deserialize_byte_buf(visitor),
+            Method::Unit => deserializer.deserialize_unit(visitor),
+            Method::UnitStruct(name) => deserializer.deserialize_unit_struct(name, visitor),
+            Method::Identifier => deserializer.deserialize_identifier(visitor),
+        }
+    }
+}
+
+macro_rules! forward_visit {
+    ($map:expr; $($method:ident($ty:ty),)*) => {
+        $(
+            fn $method<E: de::Error>(self, v: $ty) -> Result<Self::Value, E> {
+                self.visitor.$method(v).map($map)
+            }
+        )*
+    };
+}
+
+/// Hands every value on to `T`'s visitor, except those tagged `!missing`.
+struct Tags<'a, V> {
+    visitor: V,
+    missing: &'a Cell<bool>,
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for Tags<'_, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.visitor.expecting(f)
+    }
+
+    forward_visit! {
+        |value| value;
+        visit_bool(bool),
+        visit_i8(i8),
+        visit_i16(i16),
+        visit_i32(i32),
+        visit_i64(i64),
+        visit_i128(i128),
+        visit_u8(u8),
+        visit_u16(u16),
+        visit_u32(u32),
+        visit_u64(u64),
+        visit_u128(u128),
+        visit_f32(f32),
+        visit_f64(f64),
+        visit_char(char),
+        visit_str(&str),
+        visit_borrowed_str(&'de str),
+        visit_string(String),
+        visit_bytes(&[u8]),
+        visit_borrowed_bytes(&'de [u8]),
+        visit_byte_buf(Vec<u8>),
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<V::Value, E> {
+        self.visitor.visit_none()
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<V::Value, E> {
+        self.visitor.visit_unit()
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<V::Value, D::Error> {
+        self.visitor.visit_some(Untag {
+            deserializer,
+            missing: self.missing,
+        })
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<V::Value, D::Error> {
+        self.visitor.visit_newtype_struct(Untag {
+            deserializer,
+            missing: self.missing,
+        })
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<V::Value, A::Error> {
+        self.visitor.visit_seq(seq)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<V::Value, A::Error> {
+        self.visitor.visit_map(map)
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<V::Value, A::Error> {
+        let (tag, variant) = data.variant::<String>()?;
+        if tag != TAG {
+            return self.visitor.visit_enum(Retagged { tag, variant });
+        }
+        // Only a tag has a value of its own, a plain `missing` scalar is a unit variant
+        match variant.newtype_variant::<IgnoredAny>() {
+            Ok(IgnoredAny) => Err(found(self.missing)),
+            Err(_) => {
+                let unit: StrDeserializer<A::Error> = TAG.into_deserializer();
+                self.visitor.visit_enum(unit)
+            }
+        }
+    }
+}
+
+/// Reads a scalar as an enum, to see its tag.
+///
+/// A tagged scalar hands its tag to the variant's deserializer as text, while an untagged one
+/// hands over the scalar itself. Text is therefore only passed on to `T` once the variant has
+/// turned out to have no value of its own.
+struct Scalar<'a, V> {
+    method: Method,
+    visitor: V,
+    missing: &'a Cell<bool>,
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for Scalar<'_, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.visitor.expecting(f)
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<V::Value, A::Error> {
+        let method = self.method;
+        let (read, variant) = data.variant_seed(Read {
+            method,
+            visitor: self.visitor,
+        })?;
+        let (visitor, text) = match read {
+            Step::Done(value) => {
+                variant.unit_variant()?;
+                return Ok(value);
+            }
+            Step::Text(visitor, text) => (visitor, text),
+        };
+        let mut visitor = Some(visitor);
+        let value = variant.newtype_variant_seed(Content {
+            method,
+            visitor: &mut visitor,
+            tag: text.as_str(),
+        });
+        match (value, visitor) {
+            (Ok(Some(value)), _) => Ok(value),
+            (Ok(None), _) => Err(found(self.missing)),
+            (Err(_), Some(visitor)) => text.visit(visitor),
+            (Err(err), None) => Err(err),
+        }
+    }
+}
+
+/// Text read for the variant, which is either a tag or an untagged scalar.
+enum Text<'de> {
+    Borrowed(&'de str),
+    Owned(String),
+}
+
+impl<'de> Text<'de> {
+    fn as_str(&self) -> &str {
+        match self {
+            Text::Borrowed(text) => text,
+            Text::Owned(text) => text,
+        }
+    }
+
+    fn visit<V: Visitor<'de>, E: de::Error>(self, visitor: V) -> Result<V::Value, E> {
+        match self {
+            Text::Borrowed(text) => visitor.visit_borrowed_str(text),
+            Text::Owned(text) => visitor.visit_string(text),
+        }
+    }
+}
+
+enum Step<'de, V: Visitor<'de>> {
+    Done(V::Value),
+    Text(V, Text<'de>),
+}
+
+/// Reads the variant with the method `T` asked for, holding back text.
+struct Read<V> {
+    method: Method,
+    visitor: V,
+}
+
+impl<'de, V: Visitor<'de>> DeserializeSeed<'de> for Read<V> {
+    type Value = Step<'de, V>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Step<'de, V>, D::Error> {
+        self.method.call(deserializer, self)
+    }
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for Read<V> {
+    type Value = Step<'de, V>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.visitor.expecting(f)
+    }
+
+    forward_visit! {
+        Step::Done;
+        visit_bool(bool),
+        visit_i8(i8),
+        visit_i16(i16),
+        visit_i32(i32),
+        visit_i64(i64),
+        visit_i128(i128),
+        visit_u8(u8),
+        visit_u16(u16),
+        visit_u32(u32),
+        visit_u64(u64),
+        visit_u128(u128),
+        visit_f32(f32),
+        visit_f64(f64),
+        visit_char(char),
+        visit_bytes(&[u8]),
+        visit_borrowed_bytes(&'de [u8]),
+        visit_byte_buf(Vec<u8>),
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Step<'de, V>, E> {
+        Ok(Step::Text(self.visitor, Text::Owned(v.to_string())))
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Step<'de, V>, E> {
+        Ok(Step::Text(self.visitor, Text::Borrowed(v)))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Step<'de, V>, E> {
+        Ok(Step::Text(self.visitor, Text::Owned(v)))
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Step<'de, V>, E> {
+        self.visitor.visit_none().map(Step::Done)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Step<'de, V>, E> {
+        self.visitor.visit_unit().map(Step::Done)
+    }
+}
+
+/// Reads the value of a tagged scalar: nothing for `!missing`, and `T` for other tags.
+struct Content<'a, V> {
+    method: Method,
+    visitor: &'a mut Option<V>,
+    tag: &'a str,
+}
+
+impl<'de, V: Visitor<'de>> DeserializeSeed<'de> for Content<'_, V> {
+    type Value = Option<V::Value>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        match self.visitor.take() {
+            Some(visitor) if self.tag != TAG => self.method.call(deserializer, visitor).map(Some),
+            visitor => {
+                *self.visitor = visitor;
+                IgnoredAny::deserialize(deserializer).map(|_| None)
+            }
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use axum::routing::patch;
+use axum::{Json, Router};
+use http_body_util::BodyExt;
+use optional_field::Field::{self, *};
+use optional_field::{serde_optional_fields, Patch, ValidateFields};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+#[serde_optional_fields]
+#[derive(Deserialize, ValidateFields)]
+#[serde(deny_unknown_fields)]
+struct Address {
+    #[field(non_null)]
+    city: Field<String>,
+}
+
+#[serde_optional_fields]
+#[derive(Deserialize, ValidateFields)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct UserPatch {
+    #[field(non_null)]
+    user_name: Field<String>,
+    email: Field<String>,
+    #[field(nested)]
+    address: Field<Address>,
+}
+
+async fn update_user(Patch(patch): Patch<UserPatch>) -> Json<Value> {
+    let state = |field: &Field<String>| match field {
+        Missing => json!("missing"),
+        Present(val) => json!(val),
+    };
+    Json(json!({
+        "userName": state(&patch.user_name),
+        "email": state(&patch.email),
+    }))
+}
+
+async fn send(body: &str) -> (StatusCode, Value) {
+    let app = Router::new().route("/users", patch(update_user));
+    let req = Request::patch("/users")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    let res = app.oneshot(req).await.unwrap();
+    let status = res.status();
+    let bytes = res.into_body().collect().await.unwrap().to_bytes();
+    let body = serde_json::from_slice(&bytes)
+        .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&bytes).into_owned()));
+    (status, body)
+}
+
+#[tokio::test]
+async fn accepts_valid_patch() {
+    let (status, body) = send(r#"{"email": null}"#).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, json!({"userName": "missing", "email": null}));
+}
+
+#[tokio::test]
+async fn rejects_disallowed_null() {
+    let (status, body) = send(r#"{"userName": null, "address": {"city": null}}"#).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+        body,
+        json!({"errors": [
+            {"path": "userName", "message": "must not be null"},
+            {"path": "address.city", "message": "must not be null"},
+        ]})
+    );
+}
+
+#[tokio::test]
+async fn rejects_unknown_field() {
+    let (status, body) = send(r#"{"nickname": "bob"}"#).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(body.as_str().unwrap().contains("unknown field `nickname`"));
+}
+
+#[tokio::test]
+async fn rejects_non_json_body() {
+    let app = Router::new().route("/users", patch(update_user));
+    let req = Request::patch("/users").body(Body::from("{}")).unwrap();
+    let res = app.oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+}
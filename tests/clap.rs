@@ -0,0 +1,151 @@
+use clap::{value_parser, Arg, Command, CommandFactory, Parser, Subcommand};
+use optional_field::clap::{clear_flag, field_arg, FieldValueParser};
+use optional_field::clap_optional_fields;
+use optional_field::Field::{self, *};
+use optional_field::{EmptyAs, NullToken};
+
+#[clap_optional_fields]
+#[derive(Parser, Debug)]
+struct UpdateUser {
+    #[arg(long)]
+    name: Field<String>,
+    #[arg(long)]
+    email: Field<String>,
+    #[arg(
+        long,
+        value_parser = FieldValueParser::new(value_parser!(u8).range(1..)).clear_with("none")
+    )]
+    age: Field<u8>,
+    #[arg(long)]
+    id: u32,
+}
+
+fn parse(args: &[&str]) -> Result<UpdateUser, clap::Error> {
+    UpdateUser::try_parse_from(["update-user", "--id=1"].iter().chain(args))
+}
+
+#[test]
+fn not_given() {
+    let args = parse(&[]).unwrap();
+    assert_eq!(args.name, Missing);
+    assert_eq!(args.email, Missing);
+    assert_eq!(args.age, Missing);
+}
+
+#[test]
+fn set() {
+    let args = parse(&["--name", "bob", "--email=bob@example.com", "--age=42"]).unwrap();
+    assert_eq!(args.name, Present(Some("bob".to_string())));
+    assert_eq!(args.email, Present(Some("bob@example.com".to_string())));
+    assert_eq!(args.age, Present(Some(42)));
+}
+
+#[test]
+fn clear() {
+    let args = parse(&["--name=", "--no-email", "--age=none"]).unwrap();
+    assert_eq!(args.name, Present(None));
+    assert_eq!(args.email, Present(None));
+    assert_eq!(args.age, Present(None));
+
+    let args = parse(&["--name", "", "--email="]).unwrap();
+    assert_eq!(args.name, Present(None));
+    assert_eq!(args.email, Present(None));
+}
+
+#[test]
+fn clear_flag_takes_no_value() {
+    let err = parse(&["--no-email=bob"]).unwrap_err();
+    assert_eq!(err.kind(), clap::error::ErrorKind::TooManyValues);
+
+    let err = parse(&["--email"]).unwrap_err();
+    assert_eq!(err.kind(), clap::error::ErrorKind::InvalidValue);
+}
+
+#[test]
+fn clear_flag_conflicts_with_value() {
+    let err = parse(&["--email=bob@example.com", "--no-email"]).unwrap_err();
+    assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+}
+
+#[test]
+fn invalid_value() {
+    let err = parse(&["--age=0"]).unwrap_err();
+    assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+
+    let err = parse(&["--age="]).unwrap_err();
+    assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+}
+
+#[test]
+fn help_hides_default() {
+    let help = UpdateUser::command().render_help().to_string();
+    assert!(!help.contains("default"), "{}", help);
+    assert!(help.contains("--no-email"), "{}", help);
+    assert!(!help.contains("--no-id"), "{}", help);
+}
+
+#[test]
+fn builder() {
+    let cmd = Command::new("update-user")
+        .arg(field_arg(
+            Arg::new("name")
+                .long("name")
+                .value_parser(value_parser!(Field<String>)),
+        ))
+        .arg(clear_flag("name", "name"));
+    let name = |args: &[&str]| {
+        cmd.clone()
+            .try_get_matches_from(args)
+            .unwrap()
+            .remove_one::<Field<String>>("name")
+            .unwrap()
+    };
+    assert_eq!(name(&["update-user"]), Missing);
+    assert_eq!(name(&["update-user", "--name="]), Present(None));
+    assert_eq!(name(&["update-user", "--no-name"]), Present(None));
+    assert_eq!(
+        name(&["update-user", "--name=bob"]),
+        Present(Some("bob".to_string()))
+    );
+}
+
+#[test]
+fn null_token() {
+    let parser = FieldValueParser::new(value_parser!(u8))
+        .null_token(NullToken::new("none").empty_as(EmptyAs::Missing));
+    let cmd = Command::new("update-user")
+        .arg(field_arg(Arg::new("age").long("age").value_parser(parser)));
+    let age = |arg: &str| {
+        cmd.clone()
+            .try_get_matches_from(["update-user", arg])
+            .unwrap()
+            .remove_one::<Field<u8>>("age")
+            .unwrap()
+    };
+    assert_eq!(age("--age="), Missing);
+    assert_eq!(age("--age=none"), Present(None));
+    assert_eq!(age("--age=3"), Present(Some(3)));
+}
+
+#[clap_optional_fields]
+#[derive(Subcommand, Debug)]
+enum Users {
+    Update {
+        #[arg(long)]
+        email: Field<String>,
+    },
+}
+
+#[derive(Parser, Debug)]
+struct Cli {
+    #[command(subcommand)]
+    users: Users,
+}
+
+#[test]
+fn subcommand() {
+    let Users::Update { email } = Cli::parse_from(["users", "update"]).users;
+    assert_eq!(email, Missing);
+    let Users::Update { email } = Cli::parse_from(["users", "update", "--no-email"]).users;
+    assert_eq!(email, Present(None));
+}
@@ -0,0 +1,14 @@
+//! The `users` table the tests of the SQL integrations run against.
+
+/// Creates the `users` table with bob (id 1) and alice (id 2), who has no email address.
+pub const USERS: &str = "
+    CREATE TABLE users (
+        id INTEGER PRIMARY KEY NOT NULL,
+        name TEXT NOT NULL,
+        email_address TEXT,
+        age INTEGER,
+        type TEXT
+    );
+    INSERT INTO users VALUES (1, 'bob', 'bob@example.com', 42, 'admin');
+    INSERT INTO users VALUES (2, 'alice', NULL, 37, 'user');
+";
@@ -0,0 +1,120 @@
+use optional_field::Field::{self, *};
+use optional_field::{serde_optional_fields, EmptyAs, NullToken};
+use serde::{Deserialize, Deserializer};
+
+#[serde_optional_fields]
+#[derive(Deserialize, Debug, PartialEq)]
+struct UserUpdate {
+    id: u32,
+    name: Field<String>,
+    #[serde(deserialize_with = "optional_field::csv::deserialize")]
+    email: Field<String>,
+    #[serde(deserialize_with = "optional_field::csv::deserialize")]
+    age: Field<u8>,
+    #[serde(deserialize_with = "dash_is_null")]
+    team: Field<String>,
+}
+
+fn dash_is_null<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Field<String>, D::Error> {
+    optional_field::csv::deserialize_with(deserializer, NullToken::new("-"))
+}
+
+fn read(data: &str) -> Result<Vec<UserUpdate>, csv::Error> {
+    csv::Reader::from_reader(data.as_bytes())
+        .deserialize()
+        .collect()
+}
+
+#[test]
+fn cells() {
+    let updates = read(
+        "id,name,email,age,team\n\
+         1,bob,bob@example.com,42,core\n\
+         2,,,,-\n\
+         3,NULL,NULL,NULL,NULL\n",
+    )
+    .unwrap();
+    assert_eq!(
+        updates,
+        vec![
+            UserUpdate {
+                id: 1,
+                name: Present(Some("bob".to_string())),
+                email: Present(Some("bob@example.com".to_string())),
+                age: Present(Some(42)),
+                team: Present(Some("core".to_string())),
+            },
+            UserUpdate {
+                id: 2,
+                name: Present(None),
+                email: Present(None),
+                age: Present(None),
+                team: Present(None),
+            },
+            UserUpdate {
+                id: 3,
+                name: Present(Some("NULL".to_string())),
+                email: Present(None),
+                age: Present(None),
+                team: Present(Some("NULL".to_string())),
+            },
+        ]
+    );
+}
+
+#[test]
+fn absent_columns_are_missing() {
+    let updates = read("age,id\n7,1\n").unwrap();
+    assert_eq!(
+        updates,
+        vec![UserUpdate {
+            id: 1,
+            name: Missing,
+            email: Missing,
+            age: Present(Some(7)),
+            team: Missing,
+        }]
+    );
+}
+
+#[test]
+fn empty_cell_with_custom_token_is_null() {
+    let updates = read("id,team\n1,\n").unwrap();
+    assert_eq!(updates[0].team, Present(None));
+}
+
+#[test]
+fn empty_cell_policy_of_custom_token() {
+    fn empty_is_missing<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Field<String>, D::Error> {
+        optional_field::csv::deserialize_with(
+            deserializer,
+            NullToken::new("-").empty_as(EmptyAs::Missing),
+        )
+    }
+
+    #[derive(Deserialize)]
+    struct Row {
+        id: u32,
+        #[serde(deserialize_with = "empty_is_missing")]
+        team: Field<String>,
+    }
+
+    let rows = csv::Reader::from_reader("id,team\n1,\n2,-\n".as_bytes())
+        .deserialize()
+        .collect::<Result<Vec<Row>, _>>()
+        .unwrap();
+    assert_eq!((rows[0].id, &rows[0].team), (1, &Missing));
+    assert_eq!((rows[1].id, &rows[1].team), (2, &Present(None)));
+}
+
+#[test]
+fn invalid_cell() {
+    let err = read("id,age\n1,old\n").unwrap_err();
+    assert!(
+        err.to_string().contains("invalid digit found in string"),
+        "{}",
+        err
+    );
+}
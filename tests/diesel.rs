@@ -0,0 +1,137 @@
+use diesel::connection::SimpleConnection;
+use diesel::prelude::*;
+use diesel::sqlite::SqliteConnection;
+use diesel::{debug_query, sqlite::Sqlite};
+use optional_field::Field::{self, *};
+use optional_field::FieldChangeset;
+
+mod common;
+
+diesel::table! {
+    users (id) {
+        id -> Integer,
+        name -> Text,
+        email_address -> Nullable<Text>,
+        age -> Nullable<Integer>,
+    }
+}
+
+#[derive(FieldChangeset)]
+#[field(table = "users")]
+struct UserPatch {
+    name: Option<String>,
+    #[field(column = "email_address")]
+    email: Field<String>,
+    age: Field<i32>,
+}
+
+#[derive(FieldChangeset)]
+#[field(table = "users")]
+struct Rename {
+    name: String,
+}
+
+fn connection() -> SqliteConnection {
+    let mut conn = SqliteConnection::establish(":memory:").unwrap();
+    conn.batch_execute(common::USERS).unwrap();
+    conn
+}
+
+fn users(conn: &mut SqliteConnection) -> Vec<(i32, String, Option<String>, Option<i32>)> {
+    users::table.order(users::id).load(conn).unwrap()
+}
+
+#[test]
+fn update_from_changeset() {
+    let mut conn = connection();
+    let patch = UserPatch {
+        name: None,
+        email: Present(None),
+        age: Present(Some(43)),
+    };
+    let updated = diesel::update(users::table.find(1))
+        .set(&patch)
+        .execute(&mut conn)
+        .unwrap();
+    assert_eq!(updated, 1);
+    assert_eq!(
+        users(&mut conn),
+        vec![
+            (1, "bob".to_string(), None, Some(43)),
+            (2, "alice".to_string(), None, Some(37)),
+        ]
+    );
+}
+
+#[test]
+fn missing_members_are_left_out() {
+    let patch = UserPatch {
+        name: Some("robert".to_string()),
+        email: Missing,
+        age: Present(None),
+    };
+    let query = diesel::update(users::table.find(1)).set(&patch);
+    assert_eq!(
+        debug_query::<Sqlite, _>(&query).to_string(),
+        "UPDATE `users` SET `name` = ?, `age` = ? WHERE (`users`.`id` = ?) \
+         -- binds: [\"robert\", Present(None), 1]"
+    );
+}
+
+#[test]
+fn empty_changeset_is_an_error() {
+    let mut conn = connection();
+    let patch = UserPatch {
+        name: None,
+        email: Missing,
+        age: Missing,
+    };
+    let res = diesel::update(users::table.find(1))
+        .set(&patch)
+        .execute(&mut conn);
+    assert!(matches!(
+        res,
+        Err(diesel::result::Error::QueryBuilderError(_))
+    ));
+}
+
+#[test]
+fn plain_members_are_always_set() {
+    let mut conn = connection();
+    diesel::update(users::table.find(2))
+        .set(&Rename {
+            name: "alicia".to_string(),
+        })
+        .execute(&mut conn)
+        .unwrap();
+    assert_eq!(users(&mut conn)[1].1, "alicia");
+}
+
+#[test]
+fn bind_field() {
+    let mut conn = connection();
+    diesel::update(users::table)
+        .set(users::email_address.eq(Present::<String>(None)))
+        .execute(&mut conn)
+        .unwrap();
+    assert!(users(&mut conn).iter().all(|user| user.2.is_none()));
+
+    let res = diesel::update(users::table)
+        .set(users::age.eq(Missing::<i32>))
+        .execute(&mut conn);
+    assert!(matches!(
+        res,
+        Err(diesel::result::Error::SerializationError(_))
+    ));
+}
+
+#[test]
+fn filter_by_field() {
+    let mut conn = connection();
+    let ids = users::table
+        .select(users::id)
+        .filter(users::email_address.eq(Present(Some("bob@example.com"))))
+        .load::<i32>(&mut conn)
+        .unwrap();
+    assert_eq!(ids, vec![1]);
+}
@@ -0,0 +1,177 @@
+use optional_field::env::EnvLoader;
+use optional_field::Field::{self, *};
+use optional_field::{serde_optional_fields, EmptyAs};
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum Level {
+    Debug,
+    Info,
+}
+
+#[serde_optional_fields]
+#[derive(Deserialize, Debug, PartialEq)]
+struct Config {
+    name: String,
+    host: Field<String>,
+    port: Field<u16>,
+    debug: Field<bool>,
+    log_level: Field<Level>,
+    #[serde(rename = "workers")]
+    threads: Option<u8>,
+}
+
+fn load(vars: &[(&str, &str)]) -> Result<Config, optional_field::env::EnvError> {
+    EnvLoader::new("APP_").load_from(vars.iter().copied())
+}
+
+#[test]
+fn unset_is_missing() {
+    let config = load(&[("APP_NAME", "api")]).unwrap();
+    assert_eq!(
+        config,
+        Config {
+            name: "api".to_string(),
+            host: Missing,
+            port: Missing,
+            debug: Missing,
+            log_level: Missing,
+            threads: None,
+        }
+    );
+}
+
+#[test]
+fn set_values_are_parsed() {
+    let config = load(&[
+        ("APP_NAME", "api"),
+        ("APP_HOST", "localhost"),
+        ("APP_PORT", "8080"),
+        ("APP_DEBUG", "true"),
+        ("APP_LOG_LEVEL", "info"),
+        ("APP_WORKERS", "4"),
+        ("OTHER_PORT", "1"),
+    ])
+    .unwrap();
+    assert_eq!(config.host, Present(Some("localhost".to_string())));
+    assert_eq!(config.port, Present(Some(8080)));
+    assert_eq!(config.debug, Present(Some(true)));
+    assert_eq!(config.log_level, Present(Some(Level::Info)));
+    assert_eq!(config.threads, Some(4));
+}
+
+#[test]
+fn empty_is_null() {
+    let config = load(&[
+        ("APP_NAME", "api"),
+        ("APP_HOST", ""),
+        ("APP_PORT", ""),
+        ("APP_WORKERS", ""),
+    ])
+    .unwrap();
+    assert_eq!(config.host, Present(None));
+    assert_eq!(config.port, Present(None));
+    assert_eq!(config.threads, None);
+}
+
+#[test]
+fn null_token() {
+    let config: Config = EnvLoader::new("APP_")
+        .null_token("__NULL__")
+        .load_from(vec![
+            ("APP_NAME", "api"),
+            ("APP_HOST", ""),
+            ("APP_LOG_LEVEL", "__NULL__"),
+        ])
+        .unwrap();
+    assert_eq!(config.host, Present(None));
+    assert_eq!(config.log_level, Present(None));
+}
+
+#[test]
+fn empty_as() {
+    let vars = vec![("APP_NAME", ""), ("APP_HOST", ""), ("APP_PORT", "__NULL__")];
+    let config: Config = EnvLoader::new("APP_")
+        .null_token("__NULL__")
+        .empty_as(EmptyAs::Value)
+        .load_from(vars.clone())
+        .unwrap();
+    assert_eq!(config.name, "");
+    assert_eq!(config.host, Present(Some(String::new())));
+    assert_eq!(config.port, Present(None));
+
+    let config: Config = EnvLoader::new("APP_")
+        .null_token("__NULL__")
+        .empty_as(EmptyAs::Missing)
+        .load_from(vars.into_iter().skip(1).chain(Some(("APP_NAME", "api"))))
+        .unwrap();
+    assert_eq!(config.host, Missing);
+    assert_eq!(config.port, Present(None));
+}
+
+#[test]
+fn invalid_value() {
+    let err = load(&[("APP_NAME", "api"), ("APP_PORT", "http")]).unwrap_err();
+    assert_eq!(err.var(), Some("APP_PORT"));
+    assert_eq!(err.to_string(), "`APP_PORT` invalid digit found in string");
+
+    let err = load(&[("APP_NAME", "api"), ("APP_LOG_LEVEL", "trace")]).unwrap_err();
+    assert_eq!(err.var(), Some("APP_LOG_LEVEL"));
+}
+
+#[test]
+fn required_member_unset() {
+    let err = load(&[]).unwrap_err();
+    assert_eq!(err.var(), None);
+    assert_eq!(err.to_string(), "missing field `name`");
+}
+
+#[serde_optional_fields]
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct Credentials {
+    api_key: Field<String>,
+    oauth2_token: Field<String>,
+}
+
+#[test]
+fn camel_case_members() {
+    let loader = EnvLoader::new("APP_");
+    assert_eq!(loader.var("apiKey"), "APP_API_KEY");
+    assert_eq!(loader.var("oauth2Token"), "APP_OAUTH2_TOKEN");
+    assert_eq!(loader.var("URLPath"), "APP_URL_PATH");
+    assert_eq!(loader.var("log-level"), "APP_LOG_LEVEL");
+
+    let credentials: Credentials = loader
+        .load_from(vec![("APP_API_KEY", "secret"), ("APP_OAUTH2_TOKEN", "")])
+        .unwrap();
+    assert_eq!(credentials.api_key, Present(Some("secret".to_string())));
+    assert_eq!(credentials.oauth2_token, Present(None));
+}
+
+#[test]
+fn process_environment() {
+    std::env::set_var("ENV_TEST_NAME", "api");
+    std::env::set_var("ENV_TEST_PORT", "");
+    let config: Config = EnvLoader::new("ENV_TEST_").load().unwrap();
+    assert_eq!(config.name, "api");
+    assert_eq!(config.port, Present(None));
+    assert_eq!(config.host, Missing);
+}
+
+#[test]
+fn empty_as_without_null_token() {
+    let vars = vec![("APP_NAME", "api"), ("APP_HOST", "")];
+    let config: Config = EnvLoader::new("APP_")
+        .empty_as(EmptyAs::Missing)
+        .load_from(vars.clone())
+        .unwrap();
+    assert_eq!(config.host, Missing);
+
+    let config: Config = EnvLoader::new("APP_")
+        .empty_as(EmptyAs::Value)
+        .load_from(vars)
+        .unwrap();
+    assert_eq!(config.host, Present(Some(String::new())));
+}
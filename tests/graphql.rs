@@ -0,0 +1,143 @@
+use optional_field::Field::{self, *};
+
+mod async_graphql_tests {
+    use super::*;
+    use async_graphql::{
+        EmptyMutation, EmptySubscription, InputObject, MaybeUndefined, Object, Schema,
+    };
+    use futures::executor::block_on;
+
+    #[derive(InputObject)]
+    struct Patch {
+        name: Field<String>,
+        age: Field<i32>,
+    }
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn describe(&self, patch: Patch) -> String {
+            format!("{:?} {:?}", patch.name, patch.age)
+        }
+    }
+
+    fn describe(args: &str) -> String {
+        let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+        let query = format!("{{ describe(patch: {{ {} }}) }}", args);
+        let res = block_on(schema.execute(query));
+        assert!(res.errors.is_empty(), "{:?}", res.errors);
+        res.data.into_json().unwrap()["describe"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn input_object() {
+        assert_eq!(describe(""), "Missing Missing");
+        assert_eq!(describe("name: null"), "Present(None) Missing");
+        assert_eq!(
+            describe("name: \"bob\", age: 3"),
+            "Present(Some(\"bob\")) Present(Some(3))"
+        );
+    }
+
+    #[test]
+    fn schema_type_is_nullable() {
+        let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+        let sdl = schema.sdl();
+        assert!(sdl.contains("name: String"), "{}", sdl);
+        assert!(!sdl.contains("name: String!"), "{}", sdl);
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(Field::<u8>::from(MaybeUndefined::Undefined), Missing);
+        assert_eq!(Field::<u8>::from(MaybeUndefined::Null), Present(None));
+        assert_eq!(Field::from(MaybeUndefined::Value(1)), Present(Some(1)));
+
+        assert_eq!(
+            MaybeUndefined::<u8>::from(Missing),
+            MaybeUndefined::Undefined
+        );
+        assert_eq!(
+            MaybeUndefined::<u8>::from(Present(None)),
+            MaybeUndefined::Null
+        );
+        assert_eq!(
+            MaybeUndefined::from(Present(Some(1))),
+            MaybeUndefined::Value(1)
+        );
+    }
+}
+
+mod juniper_tests {
+    use super::*;
+    use juniper::{
+        graphql_object, EmptyMutation, EmptySubscription, GraphQLInputObject, Nullable, RootNode,
+        ScalarValue, Variables,
+    };
+
+    #[derive(GraphQLInputObject)]
+    struct Patch {
+        name: Field<String>,
+        age: Field<i32>,
+    }
+
+    struct Query;
+
+    #[graphql_object]
+    impl Query {
+        fn describe(patch: Patch) -> String {
+            format!("{:?} {:?}", patch.name, patch.age)
+        }
+    }
+
+    type Root = RootNode<Query, EmptyMutation, EmptySubscription>;
+
+    fn root() -> Root {
+        Root::new(Query, EmptyMutation::new(), EmptySubscription::new())
+    }
+
+    fn describe(args: &str) -> String {
+        let query = format!("{{ describe(patch: {{ {} }}) }}", args);
+        let (res, errors) = juniper::execute_sync(&query, None, &root(), &Variables::new(), &())
+            .expect("query is valid");
+        assert!(errors.is_empty(), "{:?}", errors);
+        res.as_object_value()
+            .and_then(|obj| obj.get_field_value("describe"))
+            .and_then(|value| value.as_scalar())
+            .and_then(|scalar| scalar.try_as_str())
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn input_object() {
+        assert_eq!(describe(""), "Missing Missing");
+        assert_eq!(describe("name: null"), "Present(None) Missing");
+        assert_eq!(
+            describe("name: \"bob\", age: 3"),
+            "Present(Some(\"bob\")) Present(Some(3))"
+        );
+    }
+
+    #[test]
+    fn schema_type_is_nullable() {
+        let sdl = root().as_sdl();
+        assert!(sdl.contains("name: String"), "{}", sdl);
+        assert!(!sdl.contains("name: String!"), "{}", sdl);
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(Field::<u8>::from(Nullable::ImplicitNull), Missing);
+        assert_eq!(Field::<u8>::from(Nullable::ExplicitNull), Present(None));
+        assert_eq!(Field::from(Nullable::Some(1)), Present(Some(1)));
+
+        assert_eq!(Nullable::<u8>::from(Missing), Nullable::ImplicitNull);
+        assert_eq!(Nullable::<u8>::from(Present(None)), Nullable::ExplicitNull);
+        assert_eq!(Nullable::from(Present(Some(1))), Nullable::Some(1));
+    }
+}
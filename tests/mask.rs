@@ -0,0 +1,122 @@
+use optional_field::Field::{self, *};
+use optional_field::{serde_optional_fields, PresentPaths};
+use serde::Deserialize;
+
+#[derive(Debug, Clone, PartialEq, PresentPaths)]
+struct Address {
+    city: Field<String>,
+    zip: Field<String>,
+}
+
+#[serde_optional_fields]
+#[derive(Debug, Clone, PartialEq, Deserialize, PresentPaths)]
+#[serde(rename_all = "camelCase")]
+struct UserPatch {
+    id: u32,
+    first_name: Field<String>,
+    #[serde(rename = "mail")]
+    email: Field<String>,
+    #[field(nested)]
+    #[serde(skip)]
+    home_address: Field<Address>,
+    #[field(nested)]
+    #[serde(skip)]
+    work_address: Option<Box<Address>>,
+}
+
+fn patch() -> UserPatch {
+    UserPatch {
+        id: 1,
+        first_name: Present(Some("bob".to_string())),
+        email: Present(None),
+        home_address: Present(Some(Address {
+            city: Present(Some("Oslo".to_string())),
+            zip: Present(None),
+        })),
+        work_address: Some(Box::new(Address {
+            city: Missing,
+            zip: Present(Some("0150".to_string())),
+        })),
+    }
+}
+
+#[test]
+fn present_paths() {
+    assert_eq!(
+        patch().present_paths(),
+        vec![
+            "firstName",
+            "mail",
+            "homeAddress.city",
+            "homeAddress.zip",
+            "workAddress.zip"
+        ]
+    );
+}
+
+#[test]
+fn present_paths_from_json() {
+    let patch: UserPatch = serde_json::from_str(r#"{"id": 1, "mail": null}"#).unwrap();
+    assert_eq!(patch.present_paths(), vec!["mail"]);
+}
+
+#[test]
+fn null_nested_member_is_listed_by_its_own_path() {
+    let mut patch = patch();
+    patch.home_address = Present(None);
+    patch.work_address = None;
+    assert_eq!(
+        patch.present_paths(),
+        vec!["firstName", "mail", "homeAddress"]
+    );
+}
+
+#[test]
+fn restrict_to() {
+    let mut patch = patch();
+    patch.restrict_to(&["mail", "homeAddress.zip", "workAddress"]);
+    assert_eq!(
+        patch,
+        UserPatch {
+            id: 1,
+            first_name: Missing,
+            email: Present(None),
+            home_address: Present(Some(Address {
+                city: Missing,
+                zip: Present(None),
+            })),
+            work_address: Some(Box::new(Address {
+                city: Missing,
+                zip: Present(Some("0150".to_string())),
+            })),
+        }
+    );
+}
+
+#[test]
+fn restrict_to_whole_nested_member() {
+    let mut patch = patch();
+    let expected = patch.home_address.clone();
+    patch.restrict_to(&["homeAddress".to_string()]);
+    assert_eq!(patch.home_address, expected);
+    assert_eq!(
+        patch.present_paths(),
+        vec!["homeAddress.city", "homeAddress.zip"]
+    );
+}
+
+#[test]
+fn restrict_to_present_paths_is_identity() {
+    let mut patch = patch();
+    let paths = patch.present_paths();
+    patch.restrict_to(&paths);
+    assert_eq!(patch, self::patch());
+}
+
+#[test]
+fn restrict_to_nothing() {
+    let mut patch = patch();
+    patch.restrict_to::<&str>(&[]);
+    assert_eq!(patch.present_paths(), Vec::<String>::new());
+    assert_eq!(patch.id, 1);
+}
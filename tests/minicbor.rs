@@ -0,0 +1,44 @@
+use minicbor::{Decode, Encode};
+use optional_field::Field::{self, *};
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+#[cbor(map)]
+struct Patch {
+    #[n(0)]
+    name: Field<String>,
+    #[n(1)]
+    age: Field<u8>,
+    #[n(2)]
+    scores: Vec<Field<u8>>,
+}
+
+#[test]
+fn missing_is_undefined() {
+    let fields = vec![Present(Some(3)), Missing, Present(None)];
+    let bytes = minicbor::to_vec(&fields).unwrap();
+    assert_eq!(bytes, [0x83, 0x03, 0xf7, 0xf6]);
+    assert_eq!(minicbor::len(&fields), bytes.len());
+    assert_eq!(minicbor::decode::<Vec<Field<u8>>>(&bytes).unwrap(), fields);
+}
+
+#[test]
+fn missing_members_are_left_out() {
+    let patch = Patch {
+        name: Present(None),
+        age: Missing,
+        scores: vec![Missing, Present(Some(7))],
+    };
+    let bytes = minicbor::to_vec(&patch).unwrap();
+    assert_eq!(bytes, [0xa2, 0x00, 0xf6, 0x02, 0x82, 0xf7, 0x07]);
+    assert_eq!(minicbor::decode::<Patch>(&bytes).unwrap(), patch);
+
+    // An explicit `undefined` is read as `Missing` as well.
+    let bytes = [0xa3, 0x00, 0xf6, 0x01, 0xf7, 0x02, 0x80];
+    let patch: Patch = minicbor::decode(&bytes).unwrap();
+    assert_eq!(patch.age, Missing);
+}
+
+#[test]
+fn other_values_are_typed() {
+    assert!(minicbor::decode::<Field<u8>>(&[0x61, 0x31]).is_err());
+}
@@ -0,0 +1,118 @@
+use optional_field::serde::missing::{self, MissingAs, SerializeMissing};
+use optional_field::serde_optional_fields;
+use optional_field::Field::{self, *};
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::json;
+use std::collections::BTreeMap;
+
+#[serde_optional_fields]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Patch {
+    #[serde(with = "optional_field::serde::missing::error")]
+    name: Field<String>,
+    #[serde(default, with = "optional_field::serde::missing::error::seq")]
+    tags: Vec<Field<String>>,
+    #[serde(default)]
+    labels: BTreeMap<String, MissingAs<missing::Error, String>>,
+}
+
+#[test]
+fn error_policy_skips_members() {
+    let patch = Patch {
+        name: Missing,
+        tags: vec![Present(Some("a".to_string())), Present(None)],
+        labels: BTreeMap::new(),
+    };
+    let json = serde_json::to_value(&patch).unwrap();
+
+    assert_eq!(json!({ "tags": ["a", null], "labels": {} }), json);
+    assert_eq!(patch, serde_json::from_value::<Patch>(json).unwrap());
+}
+
+#[test]
+fn error_policy_fails_in_containers() {
+    let patch = Patch {
+        name: Present(None),
+        tags: vec![Missing],
+        labels: BTreeMap::new(),
+    };
+    let err = serde_json::to_value(&patch).unwrap_err();
+    assert_eq!(
+        "`Missing` must be skipped, as it cannot be serialized here",
+        err.to_string()
+    );
+
+    let mut labels = BTreeMap::new();
+    labels.insert("team".to_string(), MissingAs::new(Missing));
+    let patch = Patch {
+        name: Present(None),
+        tags: vec![],
+        labels,
+    };
+    assert!(serde_json::to_value(&patch).is_err());
+}
+
+#[test]
+fn error_policy_without_attribute() {
+    #[derive(Serialize)]
+    struct Patch {
+        #[serde(with = "optional_field::serde::missing::error")]
+        name: Field<String>,
+    }
+
+    assert!(serde_json::to_value(Patch { name: Missing }).is_err());
+    assert_eq!(
+        json!({ "name": null }),
+        serde_json::to_value(Patch {
+            name: Present(None)
+        })
+        .unwrap()
+    );
+}
+
+#[test]
+fn null_and_unit_policies() {
+    #[derive(Serialize)]
+    struct Row {
+        #[serde(with = "optional_field::serde::missing::null")]
+        null: Field<u8>,
+        #[serde(with = "optional_field::serde::missing::unit::seq")]
+        unit: Vec<Field<u8>>,
+    }
+
+    let row = Row {
+        null: Missing,
+        unit: vec![Missing, Present(Some(1))],
+    };
+    assert_eq!(
+        json!({ "null": null, "unit": [null, 1] }),
+        serde_json::to_value(row).unwrap()
+    );
+}
+
+#[test]
+fn custom_policy() {
+    enum Undefined {}
+
+    impl SerializeMissing for Undefined {
+        fn serialize_missing<S: Serializer>(serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str("$undefined")
+        }
+    }
+
+    let fields: Vec<MissingAs<Undefined, u8>> = vec![
+        Missing.into(),
+        Present(None).into(),
+        Present(Some(2)).into(),
+    ];
+    assert_eq!(
+        json!(["$undefined", null, 2]),
+        serde_json::to_value(&fields).unwrap()
+    );
+
+    let fields: Vec<MissingAs<Undefined, u8>> = serde_json::from_value(json!([null, 2])).unwrap();
+    assert_eq!(
+        vec![Present(None), Present(Some(2))],
+        fields.into_iter().map(Field::from).collect::<Vec<_>>()
+    );
+}
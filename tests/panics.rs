@@ -0,0 +1,59 @@
+use std::cell::RefCell;
+use std::panic::{self, UnwindSafe};
+
+use optional_field::Field::{self, *};
+
+thread_local! {
+    static LOCATION: RefCell<Option<(String, u32)>> = const { RefCell::new(None) };
+}
+
+/// Run `f`, which must panic, and return the file and line the panic was reported at
+fn panic_location<F: FnOnce() + UnwindSafe>(f: F) -> (String, u32) {
+    panic::set_hook(Box::new(|info| {
+        let location = info.location().unwrap();
+        LOCATION.with(|cell| {
+            *cell.borrow_mut() = Some((location.file().to_string(), location.line()));
+        });
+    }));
+    assert!(panic::catch_unwind(f).is_err());
+    LOCATION.with(|cell| cell.borrow_mut().take()).unwrap()
+}
+
+/// A boxed call of `$call` together with the line it is written on
+macro_rules! call {
+    ($call:expr) => {
+        (
+            Box::new(move || {
+                let _ = $call;
+            }) as Box<dyn FnOnce() + UnwindSafe>,
+            line!(),
+        )
+    };
+}
+
+#[test]
+fn unwrapping_methods_report_the_callers_location() {
+    let missing = || Missing::<u8>;
+
+    let calls = vec![
+        call!(missing().unwrap()),
+        call!(Present::<u8>(None).unwrap()),
+        call!(missing().unwrap_present()),
+        call!(missing().unwrap_present_ref()),
+        call!(missing().unwrap_present_mut()),
+        call!(missing().expect("msg")),
+        call!(missing().expect_present("msg")),
+    ];
+
+    for (call, line) in calls {
+        assert_eq!((file!().to_string(), line), panic_location(call));
+    }
+}
+
+#[test]
+fn field_error() {
+    let field: Field<u8> = Present(None);
+    let err: Box<dyn std::error::Error> = Box::new(field.try_unwrap().unwrap_err());
+
+    assert_eq!("field is null", err.to_string());
+}
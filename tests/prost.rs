@@ -0,0 +1,138 @@
+use optional_field::Field::{self, *};
+use optional_field::{FieldMaskBuilder, PresentPaths};
+use prost::Message;
+use prost_types::FieldMask;
+
+/// ```proto
+/// message UpdateUserRequest {
+///   optional string name = 1;
+///   google.protobuf.UInt32Value age = 2;
+///   google.protobuf.FieldMask update_mask = 3;
+/// }
+/// ```
+#[derive(Clone, PartialEq, Message)]
+struct UpdateUserRequest {
+    #[prost(string, optional, tag = "1")]
+    name: Option<String>,
+    #[prost(message, optional, tag = "2")]
+    age: Option<u32>,
+    #[prost(message, optional, tag = "3")]
+    update_mask: Option<FieldMask>,
+}
+
+#[derive(Debug, Clone, PartialEq, PresentPaths)]
+struct UserPatch {
+    name: Field<String>,
+    age: Field<u32>,
+}
+
+impl From<UserPatch> for UpdateUserRequest {
+    fn from(patch: UserPatch) -> Self {
+        let mask = FieldMaskBuilder::new()
+            .field("name", &patch.name)
+            .field("age", &patch.age)
+            .build();
+        UpdateUserRequest {
+            name: patch.name.into_proto(),
+            age: patch.age.into_proto(),
+            update_mask: Some(mask),
+        }
+    }
+}
+
+impl From<UpdateUserRequest> for UserPatch {
+    fn from(request: UpdateUserRequest) -> Self {
+        match request.update_mask {
+            Some(mask) => UserPatch {
+                name: Field::from_masked(request.name, &mask, "name"),
+                age: Field::from_masked(request.age, &mask, "age"),
+            },
+            None => UserPatch {
+                name: Field::from_proto(request.name),
+                age: Field::from_proto(request.age),
+            },
+        }
+    }
+}
+
+fn roundtrip(patch: UserPatch) -> UserPatch {
+    let bytes = UpdateUserRequest::from(patch).encode_to_vec();
+    UpdateUserRequest::decode(bytes.as_slice()).unwrap().into()
+}
+
+#[test]
+fn roundtrip_all_states() {
+    let patches = vec![
+        UserPatch {
+            name: Missing,
+            age: Missing,
+        },
+        UserPatch {
+            name: Present(None),
+            age: Present(None),
+        },
+        UserPatch {
+            name: Present(Some("bob".to_string())),
+            age: Present(Some(0)),
+        },
+        UserPatch {
+            name: Present(Some(String::new())),
+            age: Missing,
+        },
+    ];
+    for patch in patches {
+        assert_eq!(roundtrip(patch.clone()), patch);
+    }
+}
+
+#[test]
+fn without_mask_unset_is_missing() {
+    let patch = UserPatch::from(UpdateUserRequest {
+        name: Some("bob".to_string()),
+        age: None,
+        update_mask: None,
+    });
+    assert_eq!(
+        patch,
+        UserPatch {
+            name: Present(Some("bob".to_string())),
+            age: Missing,
+        }
+    );
+}
+
+#[test]
+fn mask_parents_cover_children() {
+    let mask = FieldMask {
+        paths: vec!["address".to_string()],
+    };
+    assert_eq!(
+        Field::<u8>::from_masked(None, &mask, "address.zip"),
+        Present(None)
+    );
+    assert_eq!(Field::<u8>::from_masked(None, &mask, "addressee"), Missing);
+}
+
+#[test]
+fn builder_push_path() {
+    let mut builder = FieldMaskBuilder::new();
+    builder.push("name", &Missing::<u8>);
+    builder.push("age", &Present::<u8>(None));
+    builder.push_path("address.zip");
+    assert_eq!(builder.paths(), ["age", "address.zip"]);
+    assert_eq!(FieldMask::from(builder).paths, vec!["age", "address.zip"]);
+}
+
+#[test]
+fn mask_from_present_paths() {
+    let mut patch = UserPatch {
+        name: Present(None),
+        age: Present(Some(3)),
+    };
+    let mask = FieldMaskBuilder::new().present(&patch).build();
+    assert_eq!(mask.paths, vec!["name", "age"]);
+
+    patch.restrict_to(&["age"]);
+    let mask = FieldMaskBuilder::new().present(&patch).build();
+    assert_eq!(mask.paths, vec!["age"]);
+}
@@ -0,0 +1,243 @@
+//! `Field` members keep their three states in flattened structs and in every enum
+//! representation, where serde buffers the input before handing it on.
+
+use optional_field::serde_optional_fields;
+use optional_field::Field::{self, *};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fmt::Debug;
+
+#[serde_optional_fields]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Contact {
+    email: Field<String>,
+    phone: Field<String>,
+    age: Field<u8>,
+}
+
+fn contact() -> Contact {
+    Contact {
+        email: Present(Some("bob@example.com".to_string())),
+        phone: Present(None),
+        age: Missing,
+    }
+}
+
+/// Deserializes `json` from both a string and a `Value` and serializes it back
+fn round_trip<T>(json: Value, expected: T)
+where
+    T: Debug + PartialEq + Serialize + DeserializeOwned,
+{
+    assert_eq!(
+        expected,
+        serde_json::from_str::<T>(&json.to_string()).unwrap()
+    );
+    assert_eq!(expected, serde_json::from_value::<T>(json.clone()).unwrap());
+    assert_eq!(json, serde_json::to_value(&expected).unwrap());
+}
+
+#[test]
+fn flattened_struct() {
+    #[serde_optional_fields]
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct User {
+        name: Field<String>,
+        #[serde(flatten)]
+        contact: Contact,
+    }
+
+    round_trip(
+        json!({ "email": "bob@example.com", "phone": null }),
+        User {
+            name: Missing,
+            contact: contact(),
+        },
+    );
+    round_trip(
+        json!({ "name": null }),
+        User {
+            name: Present(None),
+            contact: Contact {
+                email: Missing,
+                phone: Missing,
+                age: Missing,
+            },
+        },
+    );
+}
+
+#[test]
+fn nested_flattened_structs() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Profile {
+        #[serde(flatten)]
+        contact: Contact,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct User {
+        id: u8,
+        #[serde(flatten)]
+        profile: Profile,
+    }
+
+    round_trip(
+        json!({ "id": 1, "email": "bob@example.com", "phone": null }),
+        User {
+            id: 1,
+            profile: Profile { contact: contact() },
+        },
+    );
+}
+
+#[test]
+fn internally_tagged_enum() {
+    #[serde_optional_fields]
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(tag = "type")]
+    enum Event {
+        Renamed {
+            name: Field<String>,
+            age: Field<u8>,
+        },
+        Contact(Contact),
+        Merged {
+            id: u8,
+            #[serde(flatten)]
+            contact: Contact,
+        },
+    }
+
+    round_trip(
+        json!({ "type": "Renamed", "name": null }),
+        Event::Renamed {
+            name: Present(None),
+            age: Missing,
+        },
+    );
+    round_trip(
+        json!({ "type": "Contact", "email": "bob@example.com", "phone": null }),
+        Event::Contact(contact()),
+    );
+    round_trip(
+        json!({ "type": "Merged", "id": 1, "email": "bob@example.com", "phone": null }),
+        Event::Merged {
+            id: 1,
+            contact: contact(),
+        },
+    );
+}
+
+#[test]
+fn adjacently_tagged_enum() {
+    #[serde_optional_fields]
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(tag = "type", content = "data")]
+    enum Event {
+        Renamed { name: Field<String>, age: Field<u8> },
+        Contact(Contact),
+        Aged(Field<u8>),
+    }
+
+    round_trip(
+        json!({ "type": "Renamed", "data": { "name": null } }),
+        Event::Renamed {
+            name: Present(None),
+            age: Missing,
+        },
+    );
+    round_trip(
+        json!({ "type": "Contact", "data": { "email": "bob@example.com", "phone": null } }),
+        Event::Contact(contact()),
+    );
+    round_trip(
+        json!({ "type": "Aged", "data": null }),
+        Event::Aged(Present(None)),
+    );
+    round_trip(
+        json!({ "type": "Aged", "data": 3 }),
+        Event::Aged(Present(Some(3))),
+    );
+}
+
+#[test]
+fn externally_tagged_enum() {
+    #[serde_optional_fields]
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Event {
+        Renamed { name: Field<String>, age: Field<u8> },
+        Aged(Field<u8>),
+    }
+
+    round_trip(
+        json!({ "Renamed": { "age": null } }),
+        Event::Renamed {
+            name: Missing,
+            age: Present(None),
+        },
+    );
+    round_trip(json!({ "Aged": null }), Event::Aged(Present(None)));
+}
+
+#[test]
+fn untagged_enum() {
+    #[serde_optional_fields]
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(untagged)]
+    enum Payload {
+        Merged {
+            id: u8,
+            #[serde(flatten)]
+            contact: Contact,
+        },
+        Renamed {
+            name: Field<String>,
+            age: Field<u8>,
+        },
+    }
+
+    round_trip(
+        json!({ "id": 1, "email": "bob@example.com", "phone": null }),
+        Payload::Merged {
+            id: 1,
+            contact: contact(),
+        },
+    );
+
+    round_trip(
+        json!({ "name": null }),
+        Payload::Renamed {
+            name: Present(None),
+            age: Missing,
+        },
+    );
+    round_trip(
+        json!({ "age": 3 }),
+        Payload::Renamed {
+            name: Missing,
+            age: Present(Some(3)),
+        },
+    );
+}
+
+#[test]
+fn flattened_field_without_the_attribute() {
+    // Without `serde_optional_fields`, which rejects them, a flattened `Field` behaves like a
+    // flattened `Option`: it is `Present` as soon as `T` can be read from the remaining keys
+    #[derive(Debug, Deserialize)]
+    struct User {
+        #[serde(flatten)]
+        contact: Field<Contact>,
+    }
+
+    let user = serde_json::from_value::<User>(json!({})).unwrap();
+    assert_eq!(
+        Present(Some(Contact {
+            email: Missing,
+            phone: Missing,
+            age: Missing,
+        })),
+        user.contact
+    );
+}
@@ -0,0 +1,156 @@
+use optional_field::rmp::Ext;
+use optional_field::serde_optional_fields;
+use optional_field::Field::{self, *};
+use serde::de::{SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::convert::TryInto;
+use std::fmt;
+
+/// An extension value of another type, which must be handed on to `T`.
+#[derive(Debug, PartialEq)]
+struct Timestamp(u32);
+
+impl Serialize for Timestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        struct Data([u8; 4]);
+
+        impl Serialize for Data {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_bytes(&self.0)
+            }
+        }
+
+        serializer.serialize_newtype_struct("_ExtStruct", &(-1i8, Data(self.0.to_be_bytes())))
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ExtVisitor;
+
+        impl<'de> Visitor<'de> for ExtVisitor {
+            type Value = Timestamp;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "a timestamp")
+            }
+
+            fn visit_newtype_struct<D: Deserializer<'de>>(
+                self,
+                deserializer: D,
+            ) -> Result<Timestamp, D::Error> {
+                deserializer.deserialize_tuple(2, self)
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Timestamp, A::Error> {
+                let ext_type: i8 = seq.next_element()?.unwrap();
+                assert_eq!(ext_type, -1);
+                let data: Data = seq.next_element()?.unwrap();
+                Ok(Timestamp(u32::from_be_bytes(data.0)))
+            }
+        }
+
+        struct Data([u8; 4]);
+
+        impl<'de> Deserialize<'de> for Data {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                struct DataVisitor;
+
+                impl<'de> Visitor<'de> for DataVisitor {
+                    type Value = Data;
+
+                    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                        write!(f, "4 bytes")
+                    }
+
+                    fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Data, E> {
+                        v.try_into()
+                            .map(Data)
+                            .map_err(|_| E::invalid_length(v.len(), &self))
+                    }
+                }
+
+                deserializer.deserialize_bytes(DataVisitor)
+            }
+        }
+
+        deserializer.deserialize_newtype_struct("_ExtStruct", ExtVisitor)
+    }
+}
+
+#[serde_optional_fields]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Patch {
+    name: Field<String>,
+    #[serde(with = "optional_field::rmp")]
+    seen: Field<Timestamp>,
+    #[serde(default, with = "optional_field::rmp::seq")]
+    scores: Vec<Field<u8>>,
+    #[serde(default)]
+    labels: BTreeMap<String, Ext<String>>,
+}
+
+#[test]
+fn missing_round_trips_in_sequences() {
+    let fields = vec![Present(Some(3)), Missing, Present(None)];
+    let bytes =
+        rmp_serde::to_vec(&fields.iter().cloned().map(Ext::<_>).collect::<Vec<_>>()).unwrap();
+    assert_eq!(bytes, [0x93, 0x03, 0xc7, 0x00, 0x00, 0xc0]);
+
+    let decoded: Vec<Ext<u8>> = rmp_serde::from_slice(&bytes).unwrap();
+    assert_eq!(
+        decoded.into_iter().map(Field::from).collect::<Vec<_>>(),
+        fields
+    );
+}
+
+#[test]
+fn patch_round_trips() {
+    let mut labels = BTreeMap::new();
+    labels.insert("team".to_string(), Ext(Present(Some("core".to_string()))));
+    labels.insert("tier".to_string(), Ext(Missing));
+    labels.insert("owner".to_string(), Ext(Present(None)));
+    let patches = [
+        Patch {
+            name: Present(None),
+            seen: Present(Some(Timestamp(1_700_000_000))),
+            scores: vec![Missing, Present(Some(7)), Present(None)],
+            labels,
+        },
+        Patch {
+            name: Missing,
+            seen: Missing,
+            scores: vec![],
+            labels: BTreeMap::new(),
+        },
+    ];
+
+    for patch in patches {
+        let bytes = rmp_serde::to_vec_named(&patch).unwrap();
+        assert_eq!(rmp_serde::from_slice::<Patch>(&bytes).unwrap(), patch);
+    }
+}
+
+#[test]
+fn other_values_are_typed() {
+    let bytes = rmp_serde::to_vec(&[Ext::<_>(Present(Some("1")))]).unwrap();
+    let err = rmp_serde::from_slice::<Vec<Ext<u8>>>(&bytes).unwrap_err();
+    assert!(err.to_string().contains("invalid type"), "{}", err);
+}
+
+#[test]
+fn other_ext_type() {
+    let fields: Vec<Ext<u8, 42>> = vec![Ext(Missing), Ext(Present(Some(3)))];
+    let bytes = rmp_serde::to_vec(&fields).unwrap();
+    assert_eq!(bytes, [0x92, 0xc7, 0x00, 42, 0x03]);
+    assert_eq!(
+        rmp_serde::from_slice::<Vec<Ext<u8, 42>>>(&bytes).unwrap(),
+        fields
+    );
+
+    // An extension value of type 0 is no longer `Missing`, but handed on to `T`
+    let bytes = rmp_serde::to_vec(&[Ext::<u8>(Missing)]).unwrap();
+    let err = rmp_serde::from_slice::<Vec<Ext<u8, 42>>>(&bytes).unwrap_err();
+    assert!(err.to_string().contains("invalid type"), "{}", err);
+}
@@ -0,0 +1,97 @@
+use optional_field::serde_optional_fields;
+use optional_field::Field;
+use schemars::{schema_for, JsonSchema};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[serde_optional_fields]
+#[derive(Serialize, Deserialize, JsonSchema)]
+struct Thing {
+    mandatory: u8,
+    option: Option<u8>,
+    field: Field<u8>,
+    #[serde(rename = "renamedField")]
+    renamed: Field<String>,
+}
+
+#[test]
+fn field_members_are_nullable_and_not_required() {
+    let schema = schema_for!(Thing);
+
+    assert_eq!(
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "Thing",
+            "type": "object",
+            "properties": {
+                "mandatory": {
+                    "type": "integer",
+                    "format": "uint8",
+                    "minimum": 0,
+                    "maximum": 255
+                },
+                "option": {
+                    "type": ["integer", "null"],
+                    "format": "uint8",
+                    "minimum": 0,
+                    "maximum": 255
+                },
+                "field": {
+                    "type": ["integer", "null"],
+                    "format": "uint8",
+                    "minimum": 0,
+                    "maximum": 255
+                },
+                "renamedField": {
+                    "type": ["string", "null"]
+                }
+            },
+            "required": ["mandatory"]
+        }),
+        schema.to_value()
+    );
+}
+
+#[test]
+fn nested_struct() {
+    #[serde_optional_fields]
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    struct Inner {
+        value: Field<bool>,
+    }
+
+    #[serde_optional_fields]
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    struct Outer {
+        inner: Field<Inner>,
+    }
+
+    let schema = schema_for!(Outer);
+
+    assert_eq!(
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "Outer",
+            "type": "object",
+            "properties": {
+                "inner": {
+                    "anyOf": [
+                        { "$ref": "#/$defs/Inner" },
+                        { "type": "null" }
+                    ]
+                }
+            },
+            "$defs": {
+                "Inner": {
+                    "type": "object",
+                    "properties": {
+                        "value": {
+                            "type": ["boolean", "null"]
+                        }
+                    }
+                }
+            }
+        }),
+        schema.to_value()
+    );
+}
@@ -145,3 +145,127 @@ fn with_fqn() {
         json
     );
 }
+
+#[test]
+fn serialize_traced() {
+    use optional_field::TracedField;
+
+    #[derive(Debug, Serialize)]
+    struct Config {
+        #[serde(skip_serializing_if = "TracedField::is_missing")]
+        host: TracedField<String, &'static str>,
+        #[serde(skip_serializing_if = "TracedField::is_missing")]
+        port: TracedField<u16, &'static str>,
+        #[serde(skip_serializing_if = "TracedField::is_missing")]
+        user: TracedField<String, &'static str>,
+    }
+
+    let mut config = Config {
+        host: Present(Some("localhost".to_string())).traced("defaults"),
+        port: Present(Some(80)).traced("defaults"),
+        user: TracedField::default(),
+    };
+    config.port.merge_field(Present(None), "env");
+
+    let json = serde_json::to_value(config).unwrap();
+
+    assert_eq!(
+        json!(
+            {
+                "host": "localhost",
+                "port": null,
+            }
+        ),
+        json
+    );
+}
+
+#[test]
+fn with_existing_default() {
+    #[serde_optional_fields]
+    #[derive(Debug, Serialize, Deserialize)]
+    struct WithDefault {
+        #[serde(default)]
+        field: Field<u8>,
+    }
+
+    let thing = serde_json::from_value::<WithDefault>(json!({})).unwrap();
+
+    assert_eq!(Missing, thing.field);
+    assert_eq!(json!({}), serde_json::to_value(thing).unwrap());
+}
+
+#[test]
+fn tuple_struct_keeps_positions() {
+    #[serde_optional_fields(positional)]
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Point(Field<u8>, u8, Field<u8>, Field<u8>);
+
+    let point = Point(Missing, 2, Present(None), Present(Some(4)));
+    let json = serde_json::to_value(&point).unwrap();
+
+    assert_eq!(json!([[], 2, [null], [4]]), json);
+    assert_eq!(point, serde_json::from_value::<Point>(json).unwrap());
+}
+
+#[test]
+fn tuple_variant_keeps_positions() {
+    #[serde_optional_fields(positional)]
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Shape {
+        Line(Field<u8>, Field<u8>),
+        Circle(Field<u8>),
+    }
+
+    for (shape, json) in [
+        (
+            Shape::Line(Missing, Present(Some(2))),
+            json!({ "Line": [[], [2]] }),
+        ),
+        (
+            Shape::Line(Present(None), Missing),
+            json!({ "Line": [[null], []] }),
+        ),
+        // A newtype variant has no positions to shift
+        (Shape::Circle(Present(Some(3))), json!({ "Circle": 3 })),
+    ] {
+        assert_eq!(json, serde_json::to_value(&shape).unwrap());
+        assert_eq!(shape, serde_json::from_value::<Shape>(json).unwrap());
+    }
+}
+
+#[test]
+fn tuple_struct_rejects_several_elements() {
+    #[serde_optional_fields(positional)]
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Pair(Field<u8>, Field<u8>);
+
+    assert!(serde_json::from_value::<Pair>(json!([[1, 2], []])).is_err());
+    assert!(serde_json::from_value::<Pair>(json!([1, []])).is_err());
+}
+
+#[test]
+fn tuple_struct_with_existing_with() {
+    #[serde_optional_fields(positional)]
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Pair(#[serde(with = "plain")] Field<u8>, Field<u8>);
+
+    mod plain {
+        use optional_field::Field;
+        use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+        pub fn serialize<S: Serializer>(field: &Field<u8>, s: S) -> Result<S::Ok, S::Error> {
+            field.serialize(s)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Field<u8>, D::Error> {
+            Field::deserialize(d)
+        }
+    }
+
+    let pair = Pair(Present(Some(1)), Missing);
+    let json = serde_json::to_value(&pair).unwrap();
+
+    assert_eq!(json!([1, []]), json);
+    assert_eq!(pair, serde_json::from_value::<Pair>(json).unwrap());
+}
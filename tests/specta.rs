@@ -0,0 +1,33 @@
+use optional_field::serde_optional_fields;
+use optional_field::Field;
+use serde::{Deserialize, Serialize};
+use specta::{Type, TypeCollection};
+use specta_typescript::Typescript;
+
+#[derive(Serialize, Deserialize, Type)]
+struct Inner {
+    value: bool,
+}
+
+#[serde_optional_fields]
+#[derive(Serialize, Deserialize, Type)]
+struct Thing {
+    mandatory: u8,
+    option: Option<u8>,
+    name: Field<String>,
+    inner: Field<Inner>,
+    #[serde(default)]
+    with_default: Field<u8>,
+}
+
+#[test]
+fn field_members_are_optional_and_nullable() {
+    let types = TypeCollection::default().register::<Thing>().clone();
+    let ts = Typescript::default().export(&types).unwrap();
+
+    assert!(ts.contains(
+        "export type Thing = { mandatory: number; option: number | null; name?: string | null; \
+         inner?: Inner | null; with_default?: number | null }"
+    ));
+    assert!(ts.contains("export type Inner = { value: boolean }"));
+}
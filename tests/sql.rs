@@ -0,0 +1,165 @@
+use optional_field::sql::{Placeholder, UpdateBuilder, Value};
+use optional_field::Field::{self, *};
+use optional_field::SqlPatch;
+use rusqlite::{params_from_iter, Connection};
+
+mod common;
+
+#[derive(SqlPatch)]
+struct UserPatch {
+    id: i64,
+    name: Field<String>,
+    #[field(column = "email_address")]
+    email: Field<String>,
+    age: Field<u32>,
+    r#type: Field<String>,
+}
+
+fn sqlite_value(value: Value) -> rusqlite::types::Value {
+    match value {
+        Value::Null => rusqlite::types::Value::Null,
+        Value::Bool(v) => rusqlite::types::Value::Integer(v as i64),
+        Value::Int(v) => rusqlite::types::Value::Integer(v),
+        Value::Float(v) => rusqlite::types::Value::Real(v),
+        Value::Text(v) => rusqlite::types::Value::Text(v),
+        Value::Bytes(v) => rusqlite::types::Value::Blob(v),
+    }
+}
+
+fn connection() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(common::USERS).unwrap();
+    conn
+}
+
+type Row = (String, Option<String>, Option<u32>, Option<String>);
+
+fn user(conn: &Connection, id: i64) -> Row {
+    conn.query_row(
+        "SELECT name, email_address, age, type FROM users WHERE id = ?",
+        [id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+    )
+    .unwrap()
+}
+
+fn update(conn: &Connection, patch: &UserPatch) -> usize {
+    let (sql, values) = UpdateBuilder::new("users")
+        .set_patch(patch)
+        .filter("id", patch.id)
+        .build()
+        .unwrap();
+    conn.execute(&sql, params_from_iter(values.into_iter().map(sqlite_value)))
+        .unwrap()
+}
+
+#[test]
+fn update_sqlite() {
+    let conn = connection();
+    let patch = UserPatch {
+        id: 1,
+        name: Present(Some("robert".to_string())),
+        email: Present(None),
+        age: Missing,
+        r#type: Missing,
+    };
+    assert_eq!(update(&conn, &patch), 1);
+    assert_eq!(
+        user(&conn, 1),
+        (
+            "robert".to_string(),
+            None,
+            Some(42),
+            Some("admin".to_string())
+        )
+    );
+    assert_eq!(user(&conn, 2).0, "alice");
+}
+
+#[test]
+fn statement() {
+    let patch = UserPatch {
+        id: 1,
+        name: Missing,
+        email: Present(Some("bob@example.org".to_string())),
+        age: Present(None),
+        r#type: Present(Some("user".to_string())),
+    };
+    let (sql, values) = UpdateBuilder::new("users")
+        .set_patch(&patch)
+        .filter("id", 1)
+        .filter("deleted_at", None::<i64>)
+        .placeholder(Placeholder::Dollar)
+        .build()
+        .unwrap();
+    assert_eq!(
+        sql,
+        "UPDATE users SET email_address = $1, age = NULL, type = $2 \
+         WHERE id = $3 AND deleted_at IS NULL"
+    );
+    assert_eq!(
+        values,
+        vec![
+            Value::Text("bob@example.org".to_string()),
+            Value::Text("user".to_string()),
+            Value::Int(1),
+        ]
+    );
+}
+
+#[test]
+fn placeholder_styles() {
+    let builder = UpdateBuilder::new("users")
+        .set("name", &Present(Some("bob")))
+        .set("email_address", &Present(None::<&str>))
+        .set("age", &Present(Some(43)))
+        .filter("id", 1);
+
+    let (question, _) = builder.clone().build().unwrap();
+    assert_eq!(
+        question,
+        "UPDATE users SET name = ?, email_address = NULL, age = ? WHERE id = ?"
+    );
+
+    let (dollar, values) = builder.placeholder(Placeholder::Dollar).build().unwrap();
+    assert_eq!(
+        dollar,
+        "UPDATE users SET name = $1, email_address = NULL, age = $2 WHERE id = $3"
+    );
+    assert_eq!(
+        values,
+        vec![
+            Value::Text("bob".to_string()),
+            Value::Int(43),
+            Value::Int(1)
+        ]
+    );
+}
+
+#[test]
+fn nothing_to_update() {
+    let patch = UserPatch {
+        id: 1,
+        name: Missing,
+        email: Missing,
+        age: Missing,
+        r#type: Missing,
+    };
+    assert!(patch.assignments().is_empty());
+    assert_eq!(UpdateBuilder::new("users").set_patch(&patch).build(), None);
+}
+
+#[test]
+fn set_single_fields() {
+    let builder = UpdateBuilder::new("users")
+        .set("name", &Present(Some("bob")))
+        .set("age", &Missing::<u32>);
+    assert_eq!(builder.assignments().len(), 1);
+    assert_eq!(
+        builder.build(),
+        Some((
+            "UPDATE users SET name = ?".to_string(),
+            vec![Value::Text("bob".to_string())]
+        ))
+    );
+}
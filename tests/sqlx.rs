@@ -0,0 +1,133 @@
+use optional_field::sql::UpdateBuilder;
+use optional_field::Field::{self, *};
+use optional_field::{FieldError, SqlPatch};
+use sqlx::{Connection, Row, SqliteConnection};
+
+mod common;
+
+#[derive(SqlPatch)]
+struct UserPatch {
+    id: i64,
+    name: Field<String>,
+    #[field(column = "email_address")]
+    email: Field<String>,
+    age: Field<i64>,
+}
+
+async fn connection() -> SqliteConnection {
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+    sqlx::raw_sql(common::USERS)
+        .execute(&mut conn)
+        .await
+        .unwrap();
+    conn
+}
+
+#[tokio::test]
+async fn bind_fields() {
+    let mut conn = connection().await;
+
+    sqlx::query("UPDATE users SET email_address = ?, age = ? WHERE id = 1")
+        .bind(Present::<String>(None))
+        .bind(Present(Some(43_i64)))
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+    let row = sqlx::query("SELECT email_address, age FROM users WHERE id = 1")
+        .fetch_one(&mut conn)
+        .await
+        .unwrap();
+    assert_eq!(row.get::<Field<String>, _>(0), Present(None));
+    assert_eq!(row.get::<Field<i64>, _>(1), Present(Some(43)));
+}
+
+#[tokio::test]
+async fn null_columns_are_compatible() {
+    let mut conn = connection().await;
+
+    let emails: Vec<(Field<String>,)> =
+        sqlx::query_as("SELECT email_address FROM users ORDER BY id")
+            .fetch_all(&mut conn)
+            .await
+            .unwrap();
+    assert_eq!(
+        emails,
+        vec![
+            (Present(Some("bob@example.com".to_string())),),
+            (Present(None),),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn bind_missing_is_an_error() {
+    let mut conn = connection().await;
+
+    let err = sqlx::query("UPDATE users SET age = ? WHERE id = 1")
+        .bind(Missing::<i64>)
+        .execute(&mut conn)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, sqlx::Error::Encode(_)), "{:?}", err);
+    assert!(
+        err.to_string().ends_with(&FieldError::Missing.to_string()),
+        "{}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn update_from_patch() {
+    let mut conn = connection().await;
+    let patch = UserPatch {
+        id: 1,
+        name: Present(Some("robert".to_string())),
+        email: Present(None),
+        age: Missing,
+    };
+
+    let mut query = UpdateBuilder::new("users")
+        .set_patch(&patch)
+        .filter("id", patch.id)
+        .into_query_builder()
+        .unwrap();
+    let res = query.build().execute(&mut conn).await.unwrap();
+    assert_eq!(res.rows_affected(), 1);
+
+    let rows = sqlx::query("SELECT name, email_address, age FROM users ORDER BY id")
+        .fetch_all(&mut conn)
+        .await
+        .unwrap();
+    let rows = rows
+        .iter()
+        .map(|row| {
+            (
+                row.get::<String, _>(0),
+                row.get::<Option<String>, _>(1),
+                row.get::<Option<i64>, _>(2),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        rows,
+        vec![
+            ("robert".to_string(), None, Some(42)),
+            ("alice".to_string(), None, Some(37)),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn empty_patch_builds_no_query() {
+    let patch = UserPatch {
+        id: 1,
+        name: Missing,
+        email: Missing,
+        age: Missing,
+    };
+    let query = UpdateBuilder::new("users")
+        .set_patch(&patch)
+        .into_query_builder::<sqlx::Sqlite>();
+    assert!(query.is_none());
+}
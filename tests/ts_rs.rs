@@ -0,0 +1,37 @@
+use optional_field::serde_optional_fields;
+use optional_field::Field;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+#[derive(Serialize, Deserialize, TS)]
+struct Inner {
+    value: bool,
+}
+
+#[serde_optional_fields]
+#[derive(Serialize, Deserialize, TS)]
+struct Thing {
+    mandatory: u8,
+    option: Option<u8>,
+    name: Field<String>,
+    inner: Field<Inner>,
+    #[ts(optional)]
+    not_nullable: Field<u8>,
+    #[serde(default)]
+    with_default: Field<u8>,
+}
+
+#[test]
+fn field_members_are_optional_and_nullable() {
+    assert_eq!(
+        "type Thing = { mandatory: number, option: number | null, name?: string | null, \
+         inner?: Inner | null, not_nullable?: number, with_default?: number | null, };",
+        Thing::decl()
+    );
+}
+
+#[test]
+fn field_is_typed_like_option() {
+    assert_eq!("string | null", <Field<String>>::inline());
+    assert_eq!(<Option<Inner>>::name(), <Field<Inner>>::name());
+}
@@ -0,0 +1,157 @@
+//! `Field` members in `#[serde(untagged)]` enums, where the first variant that can be read from
+//! the payload wins.
+
+use optional_field::serde_optional_fields;
+use optional_field::Field::{self, *};
+use serde::Deserialize;
+use serde_json::json;
+
+#[test]
+fn field_variants_match_any_object() {
+    #[serde_optional_fields]
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(untagged)]
+    enum Webhook {
+        Paid {
+            id: Field<u64>,
+            amount: Field<u64>,
+        },
+        Refunded {
+            id: Field<u64>,
+            reason: Field<String>,
+        },
+    }
+
+    // Every member can be left out and unknown keys are ignored, so `Paid` always matches
+    let webhook = serde_json::from_value::<Webhook>(json!({ "id": 1, "reason": "late" })).unwrap();
+    assert_eq!(
+        Webhook::Paid {
+            id: Present(Some(1)),
+            amount: Missing,
+        },
+        webhook
+    );
+}
+
+#[test]
+fn deny_unknown_fields_matches_by_keys() {
+    #[serde_optional_fields]
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(untagged, deny_unknown_fields)]
+    enum Webhook {
+        Paid {
+            id: Field<u64>,
+            amount: Field<u64>,
+        },
+        Refunded {
+            id: Field<u64>,
+            reason: Field<String>,
+        },
+    }
+
+    let webhook = serde_json::from_value::<Webhook>(json!({ "id": 1, "reason": "late" })).unwrap();
+    assert_eq!(
+        Webhook::Refunded {
+            id: Present(Some(1)),
+            reason: Present(Some("late".to_string())),
+        },
+        webhook
+    );
+
+    // Keys shared by both variants still match the first one
+    let webhook = serde_json::from_value::<Webhook>(json!({ "id": null })).unwrap();
+    assert_eq!(
+        Webhook::Paid {
+            id: Present(None),
+            amount: Missing,
+        },
+        webhook
+    );
+}
+
+#[serde_optional_fields]
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(untagged, deny_unknown_fields)]
+enum Webhook {
+    Paid {
+        id: Field<u64>,
+        #[serde(with = "optional_field::serde::strict")]
+        amount: Field<u64>,
+    },
+    Voided {
+        id: Field<u64>,
+        amount: Field<u64>,
+    },
+}
+
+#[test]
+fn strict_null_falls_through() {
+    let webhook = serde_json::from_value::<Webhook>(json!({ "id": 1, "amount": null })).unwrap();
+    assert_eq!(
+        Webhook::Voided {
+            id: Present(Some(1)),
+            amount: Present(None),
+        },
+        webhook
+    );
+}
+
+#[test]
+fn strict_accepts_missing_and_values() {
+    let webhook = serde_json::from_value::<Webhook>(json!({ "id": 1, "amount": 5 })).unwrap();
+    assert_eq!(
+        Webhook::Paid {
+            id: Present(Some(1)),
+            amount: Present(Some(5)),
+        },
+        webhook
+    );
+
+    let webhook = serde_json::from_value::<Webhook>(json!({ "id": 1 })).unwrap();
+    assert_eq!(
+        Webhook::Paid {
+            id: Present(Some(1)),
+            amount: Missing,
+        },
+        webhook
+    );
+}
+
+#[test]
+fn required_member_selects_variant() {
+    #[serde_optional_fields]
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(untagged)]
+    enum Webhook {
+        Refunded { reason: String, id: Field<u64> },
+        Paid { id: Field<u64>, amount: Field<u64> },
+    }
+
+    let webhook = serde_json::from_value::<Webhook>(json!({ "id": 1, "amount": null })).unwrap();
+    assert_eq!(
+        Webhook::Paid {
+            id: Present(Some(1)),
+            amount: Present(None),
+        },
+        webhook
+    );
+}
+
+#[test]
+fn strict_error() {
+    #[serde_optional_fields]
+    #[derive(Debug, Deserialize)]
+    struct Payment {
+        #[serde(with = "optional_field::serde::strict")]
+        amount: Field<u64>,
+    }
+
+    let payment = serde_json::from_str::<Payment>(r#"{}"#).unwrap();
+    assert_eq!(Missing, payment.amount);
+
+    let err = serde_json::from_str::<Payment>(r#"{"amount": null}"#).unwrap_err();
+    assert_eq!(
+        "invalid type: null, expected a value other than null at line 1 column 16",
+        err.to_string()
+    );
+}
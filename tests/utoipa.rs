@@ -0,0 +1,101 @@
+use optional_field::serde_optional_fields;
+use optional_field::Field;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::{PartialSchema, ToSchema};
+
+#[derive(Serialize, Deserialize, ToSchema)]
+struct Inner {
+    value: bool,
+}
+
+#[serde_optional_fields]
+#[derive(Serialize, Deserialize, ToSchema)]
+struct Thing {
+    mandatory: u8,
+    option: Option<u8>,
+    field: Field<u8>,
+    inner: Field<Inner>,
+    #[schema(value_type = Option<String>, format = Date)]
+    custom: Field<String>,
+}
+
+#[test]
+fn field_members_are_nullable_and_not_required() {
+    let schema = serde_json::to_value(Thing::schema()).unwrap();
+
+    assert_eq!(
+        json!({
+            "type": "object",
+            "required": ["mandatory"],
+            "properties": {
+                "mandatory": {
+                    "type": "integer",
+                    "format": "int32",
+                    "minimum": 0
+                },
+                "option": {
+                    "type": ["integer", "null"],
+                    "format": "int32",
+                    "minimum": 0
+                },
+                "field": {
+                    "type": ["integer", "null"],
+                    "format": "int32",
+                    "minimum": 0
+                },
+                "inner": {
+                    "oneOf": [
+                        { "type": "null" },
+                        { "$ref": "#/components/schemas/Inner" }
+                    ]
+                },
+                "custom": {
+                    "type": ["string", "null"],
+                    "format": "date"
+                }
+            }
+        }),
+        schema
+    );
+}
+
+#[test]
+fn nested_schemas_are_collected() {
+    let mut schemas = Vec::new();
+    Thing::schemas(&mut schemas);
+
+    assert_eq!(
+        vec!["Inner"],
+        schemas
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn field_implements_schema_traits() {
+    assert_eq!(
+        serde_json::to_value(<Option<u8>>::schema()).unwrap(),
+        serde_json::to_value(<Field<u8>>::schema()).unwrap()
+    );
+    assert_eq!("Field_Inner", <Field<Inner>>::name());
+}
+
+#[test]
+fn field_component_keeps_inner_schema() {
+    let components = utoipa::openapi::ComponentsBuilder::new()
+        .schema_from::<Inner>()
+        .schema_from::<Field<Inner>>()
+        .build();
+
+    assert_eq!(
+        serde_json::to_value(Inner::schema()).unwrap(),
+        serde_json::to_value(&components.schemas["Inner"]).unwrap()
+    );
+    assert_eq!(
+        serde_json::to_value(<Field<Inner>>::schema()).unwrap(),
+        serde_json::to_value(&components.schemas["Field_Inner"]).unwrap()
+    );
+}
@@ -0,0 +1,216 @@
+use std::convert::TryFrom;
+
+use optional_field::validation::{Mode, ValidationErrors, Violation};
+use optional_field::Field::{self, *};
+use optional_field::{serde_optional_fields, TryIntoModel, ValidateFields};
+use serde::Deserialize;
+
+fn no_admin(field: &Field<String>) -> Result<(), Violation> {
+    match field {
+        Present(Some(name)) if name == "admin" => Err(Violation::Custom("is reserved".into())),
+        _ => Ok(()),
+    }
+}
+
+#[serde_optional_fields]
+#[derive(Deserialize, ValidateFields)]
+struct Tag {
+    #[field(required, non_empty)]
+    label: Field<String>,
+}
+
+#[serde_optional_fields]
+#[derive(Deserialize, ValidateFields)]
+#[serde(rename_all = "camelCase")]
+struct UserPatch {
+    #[field(non_null, len(min = 2, max = 5), custom = "no_admin")]
+    user_name: Field<String>,
+    #[serde(rename = "mail")]
+    #[field(required)]
+    email: Field<String>,
+    #[field(nested)]
+    tags: Vec<Tag>,
+}
+
+fn violations(errors: ValidationErrors) -> Vec<(String, Violation)> {
+    errors
+        .into_iter()
+        .map(|error| (error.path, error.violation))
+        .collect()
+}
+
+#[test]
+fn valid() {
+    let patch = UserPatch {
+        user_name: Missing,
+        email: Present(Some("bob@example.com".to_string())),
+        tags: vec![],
+    };
+
+    assert_eq!(Ok(()), patch.validate_fields());
+}
+
+#[test]
+fn collects_all_violations_with_wire_paths() {
+    let patch = UserPatch {
+        user_name: Present(Some("admin".to_string())),
+        email: Missing,
+        tags: vec![
+            Tag {
+                label: Present(Some("ok".to_string())),
+            },
+            Tag {
+                label: Present(Some(String::new())),
+            },
+            Tag {
+                label: Present(None),
+            },
+        ],
+    };
+
+    assert_eq!(
+        vec![
+            (
+                "userName".to_string(),
+                Violation::Custom("is reserved".into())
+            ),
+            ("mail".to_string(), Violation::Missing),
+            ("tags[1].label".to_string(), Violation::Empty),
+            ("tags[2].label".to_string(), Violation::Null),
+        ],
+        violations(patch.validate_fields().unwrap_err())
+    );
+}
+
+#[test]
+fn first_failing_rule_of_a_member_wins() {
+    let patch = UserPatch {
+        user_name: Present(Some("a".to_string())),
+        email: Present(None),
+        tags: vec![],
+    };
+
+    assert_eq!(
+        vec![
+            (
+                "userName".to_string(),
+                Violation::Length {
+                    min: Some(2),
+                    max: Some(5),
+                    actual: 1
+                }
+            ),
+            ("mail".to_string(), Violation::Null),
+        ],
+        violations(patch.validate_fields().unwrap_err())
+    );
+}
+
+#[serde_optional_fields]
+#[derive(Deserialize, ValidateFields)]
+struct Owner {
+    #[field(required_on = "create")]
+    id: Field<u32>,
+}
+
+#[serde_optional_fields]
+#[derive(Deserialize, ValidateFields)]
+struct ProjectDto {
+    #[field(required_on = "create", non_empty)]
+    name: Field<String>,
+    #[field(required_on = "update")]
+    version: Field<u32>,
+    #[field(required_on = "create", nested)]
+    owner: Field<Owner>,
+}
+
+#[test]
+fn modes() {
+    let dto: ProjectDto = serde_json::from_str(r#"{"name": null, "owner": {}}"#).unwrap();
+
+    assert_eq!(Ok(()), dto.validate_fields());
+    assert_eq!(
+        vec![
+            ("name".to_string(), Violation::Null),
+            ("owner.id".to_string(), Violation::Missing),
+        ],
+        violations(dto.validate_for(Mode::Create).unwrap_err())
+    );
+    assert_eq!(
+        vec![("version".to_string(), Violation::Missing)],
+        violations(dto.validate_for(Mode::Update).unwrap_err())
+    );
+}
+
+#[derive(Debug, PartialEq)]
+struct Project {
+    name: String,
+    description: Option<String>,
+    tags: Vec<String>,
+}
+
+#[serde_optional_fields]
+#[derive(Deserialize, TryIntoModel)]
+#[serde(rename_all = "camelCase")]
+#[field(model = "Project")]
+struct CreateProject {
+    name: Field<String>,
+    #[serde(rename = "desc")]
+    description: Field<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[test]
+fn try_into_model() {
+    let create: CreateProject =
+        serde_json::from_str(r#"{"name": "crate", "desc": null, "tags": ["rust"]}"#).unwrap();
+
+    assert_eq!(
+        Ok(Project {
+            name: "crate".to_string(),
+            description: None,
+            tags: vec!["rust".to_string()],
+        }),
+        Project::try_from(create)
+    );
+}
+
+#[test]
+fn try_into_model_reports_every_member() {
+    let create: CreateProject = serde_json::from_str(r#"{"name": null}"#).unwrap();
+
+    assert_eq!(
+        vec![
+            ("name".to_string(), Violation::Null),
+            ("desc".to_string(), Violation::Missing),
+        ],
+        violations(Project::try_from(create).unwrap_err())
+    );
+}
+
+#[derive(Deserialize, ValidateFields, TryIntoModel)]
+#[field(model = "Project")]
+struct RenameProject {
+    #[field(non_null)]
+    name: Field<String>,
+    description: Field<String>,
+    tags: Vec<String>,
+}
+
+#[test]
+fn validate_then_convert() {
+    let rename = RenameProject {
+        name: Present(None),
+        description: Present(None),
+        tags: vec![],
+    };
+    assert_eq!(
+        vec![("name".to_string(), Violation::Null)],
+        violations(rename.validate_fields().unwrap_err())
+    );
+    assert_eq!(
+        vec![("name".to_string(), Violation::Null)],
+        violations(Project::try_from(rename).unwrap_err())
+    );
+}
@@ -0,0 +1,79 @@
+use optional_field::Field::{self, *};
+use validator::Validate;
+
+#[derive(Debug, Validate)]
+struct Address {
+    #[validate(length(min = 1))]
+    city: String,
+}
+
+#[derive(Debug, Validate)]
+struct UserPatch {
+    #[validate(length(max = 5))]
+    name: Field<String>,
+    #[validate(range(min = 18))]
+    age: Field<u8>,
+    #[validate(email)]
+    email: Field<String>,
+    #[validate(required)]
+    id: Field<u32>,
+    #[validate(nested)]
+    address: Field<Address>,
+}
+
+fn valid() -> UserPatch {
+    UserPatch {
+        name: Missing,
+        age: Present(None),
+        email: Present(Some("bob@example.com".to_string())),
+        id: Present(Some(1)),
+        address: Missing,
+    }
+}
+
+#[test]
+fn missing_and_null_pass_value_rules() {
+    assert!(valid().validate().is_ok());
+}
+
+#[test]
+fn values_are_checked() {
+    let patch = UserPatch {
+        name: Present(Some("too long".to_string())),
+        age: Present(Some(17)),
+        email: Present(Some("nope".to_string())),
+        ..valid()
+    };
+
+    let errors = patch.validate().unwrap_err();
+    let fields = errors.field_errors();
+
+    assert!(fields.contains_key("name"));
+    assert!(fields.contains_key("age"));
+    assert!(fields.contains_key("email"));
+    assert_eq!(3, fields.len());
+}
+
+#[test]
+fn required_rejects_missing_and_null() {
+    for id in [Missing, Present(None)] {
+        let patch = UserPatch { id, ..valid() };
+        assert!(patch
+            .validate()
+            .unwrap_err()
+            .field_errors()
+            .contains_key("id"));
+    }
+}
+
+#[test]
+fn nested() {
+    let patch = UserPatch {
+        address: Present(Some(Address {
+            city: String::new(),
+        })),
+        ..valid()
+    };
+
+    assert!(patch.validate().is_err());
+}
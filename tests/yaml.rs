@@ -0,0 +1,131 @@
+use optional_field::serde_optional_fields;
+use optional_field::yaml::Tagged;
+use optional_field::Field::{self, *};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Strategy {
+    Recreate,
+    Rolling(u8),
+}
+
+#[serde_optional_fields]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Overlay {
+    image: Field<String>,
+    replicas: Field<u8>,
+    #[serde(with = "optional_field::yaml")]
+    strategy: Field<Strategy>,
+    #[serde(default, with = "optional_field::yaml::seq")]
+    ports: Vec<Field<u16>>,
+    #[serde(default)]
+    labels: BTreeMap<String, Tagged<String>>,
+}
+
+macro_rules! yaml_tests {
+    ($($name:ident => $yaml:ident,)*) => {
+        $(
+            mod $name {
+                use super::*;
+
+                #[test]
+                fn absent_and_null_keys() {
+                    let overlay: Overlay =
+                        ::$yaml::from_str("image: ~\nreplicas: null\nstrategy:\n").unwrap();
+                    assert_eq!(overlay.image, Present(None));
+                    assert_eq!(overlay.replicas, Present(None));
+                    assert_eq!(overlay.strategy, Present(None));
+
+                    let overlay: Overlay = ::$yaml::from_str("labels: {}").unwrap();
+                    assert_eq!(overlay.image, Missing);
+                    assert_eq!(overlay.replicas, Missing);
+                    assert_eq!(overlay.strategy, Missing);
+                }
+
+                #[test]
+                fn missing_tag() {
+                    let overlay: Overlay = ::$yaml::from_str(
+                        "strategy: !missing\n\
+                         ports: [80, !missing, ~, !missing null]\n\
+                         labels:\n  team: core\n  tier: !missing\n  owner: ~\n",
+                    )
+                    .unwrap();
+                    assert_eq!(overlay.strategy, Missing);
+                    assert_eq!(
+                        overlay.ports,
+                        vec![Present(Some(80)), Missing, Present(None), Missing]
+                    );
+                    assert_eq!(overlay.labels["team"].0, Present(Some("core".to_string())));
+                    assert_eq!(overlay.labels["tier"].0, Missing);
+                    assert_eq!(overlay.labels["owner"].0, Present(None));
+                }
+
+                #[test]
+                fn other_tags_are_kept() {
+                    let overlay: Overlay =
+                        ::$yaml::from_str("strategy: !Rolling 2\nreplicas: 3").unwrap();
+                    assert_eq!(overlay.strategy, Present(Some(Strategy::Rolling(2))));
+                    assert_eq!(overlay.replicas, Present(Some(3)));
+
+                    let overlay: Overlay = ::$yaml::from_str("strategy: Recreate").unwrap();
+                    assert_eq!(overlay.strategy, Present(Some(Strategy::Recreate)));
+                }
+
+                #[test]
+                fn round_trip() {
+                    let mut labels = BTreeMap::new();
+                    labels.insert("team".to_string(), Tagged(Present(Some("core".to_string()))));
+                    labels.insert("tier".to_string(), Tagged(Missing));
+                    let overlay = Overlay {
+                        image: Present(None),
+                        replicas: Missing,
+                        strategy: Present(Some(Strategy::Rolling(1))),
+                        ports: vec![Missing, Present(None), Present(Some(443))],
+                        labels,
+                    };
+                    let yaml = ::$yaml::to_string(&overlay).unwrap();
+                    assert_eq!(
+                        yaml,
+                        "image: null\n\
+                         strategy: !Rolling 1\n\
+                         ports:\n- !missing null\n- null\n- 443\n\
+                         labels:\n  team: core\n  tier: !missing null\n"
+                    );
+                    assert_eq!(::$yaml::from_str::<Overlay>(&yaml).unwrap(), overlay);
+                }
+
+                #[test]
+                fn plain_scalars_as_strings() {
+                    let overlay: Overlay = ::$yaml::from_str(
+                        "labels:\n  version: 1.25\n  build: 123\n  stable: true\n  tier: missing\n",
+                    )
+                    .unwrap();
+                    assert_eq!(overlay.labels["version"].0, Present(Some("1.25".to_string())));
+                    assert_eq!(overlay.labels["build"].0, Present(Some("123".to_string())));
+                    assert_eq!(overlay.labels["stable"].0, Present(Some("true".to_string())));
+                    assert_eq!(overlay.labels["tier"].0, Present(Some("missing".to_string())));
+                }
+
+                #[test]
+                fn sequences_and_mappings() {
+                    let fields: Vec<Tagged<Vec<u16>>> =
+                        ::$yaml::from_str("- [80, 443]\n- !missing\n- ~\n").unwrap();
+                    assert_eq!(fields[0].0, Present(Some(vec![80, 443])));
+                    assert_eq!(fields[1].0, Missing);
+                    assert_eq!(fields[2].0, Present(None));
+
+                    let fields: Vec<Tagged<BTreeMap<String, u8>>> =
+                        ::$yaml::from_str("- {a: 1}\n- !missing null\n").unwrap();
+                    assert_eq!(fields[0].0, Present(Some(BTreeMap::from([("a".to_string(), 1)]))));
+                    assert_eq!(fields[1].0, Missing);
+                }
+            }
+        )*
+    };
+}
+
+yaml_tests! {
+    serde_yaml => serde_yaml,
+    serde_norway => serde_norway,
+}